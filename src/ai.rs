@@ -0,0 +1,231 @@
+//! How enemies move around a room between the player's steps.
+
+use super::consts::Position;
+use super::enemy::{Behaviour, Enemy};
+use super::event::GameEvent;
+use super::game::Game;
+use super::room::Room;
+use rand::Rng;
+use std::collections::VecDeque;
+
+// Floor tiles enemies can stand on, walls and door gaps excluded
+const MIN_X: u8 = 2;
+const MAX_X: u8 = 11;
+const MIN_Y: u8 = 2;
+const MAX_Y: u8 = 6;
+
+const STEPS: [(i8, i8); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+enum Plan {
+    Stay,
+    MoveTo(Position),
+    /// Confront the player, which opens the fight prompt.
+    Attack,
+}
+
+/// Moves the enemies of the current room after each player move.
+pub struct EnemyAi {}
+
+impl EnemyAi {
+    /// Gives every enemy in the room one move. The first enemy to confront the player starts a
+    /// fight.
+    pub fn take_turn(game: &mut Game) {
+        let player = game.player.position;
+        let mut attacker = None;
+        for index in 0..game.current_room.enemies.len() {
+            let mut enemy = game.current_room.enemies[index];
+            let occupied: Vec<Position> = game
+                .current_room
+                .enemies
+                .iter()
+                .map(|other| other.position)
+                .filter(|position| *position != enemy.position)
+                .chain([player])
+                .collect();
+
+            match EnemyAi::plan_move(&mut enemy, player, &occupied, &mut game.rng) {
+                Plan::Stay => {}
+                Plan::Attack => attacker = attacker.or(Some(enemy)),
+                Plan::MoveTo(target) => {
+                    let from = enemy.position;
+                    enemy.position = target;
+                    game.events.push(GameEvent::EnemyMoved { enemy, from });
+                }
+            }
+            // Chasers strike as soon as they catch up
+            if enemy.behaviour() == Behaviour::Chase && is_adjacent(enemy.position, player) {
+                attacker = attacker.or(Some(enemy));
+            }
+            game.current_room.enemies[index] = enemy;
+        }
+
+        if let Some(enemy) = attacker {
+            game.open_fight_prompt(enemy);
+        }
+    }
+
+    /// Moves an enemy standing on `position` to the closest free floor tile. Enemies can end up
+    /// next to a door while the player is away, this clears the way for the player coming in.
+    pub fn make_way(room: &mut Room, position: Position) {
+        let Some(index) = room
+            .enemies
+            .iter()
+            .position(|enemy| enemy.position == position)
+        else {
+            return;
+        };
+        let taken: Vec<Position> = room.enemies.iter().map(|enemy| enemy.position).collect();
+        let mut seen = vec![position];
+        let mut queue = VecDeque::from([position]);
+        while let Some(current) = queue.pop_front() {
+            for (dx, dy) in STEPS {
+                let Some(next) = step(current, dx, dy) else {
+                    continue;
+                };
+                if seen.contains(&next) {
+                    continue;
+                }
+                if !taken.contains(&next) {
+                    room.enemies[index].position = next;
+                    return;
+                }
+                seen.push(next);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    /// Picks what `enemy` does this turn.
+    fn plan_move(
+        enemy: &mut Enemy,
+        player: Position,
+        occupied: &[Position],
+        rng: &mut impl Rng,
+    ) -> Plan {
+        let free = |target: &Position| !occupied.contains(target);
+        let target = match enemy.behaviour() {
+            Behaviour::Guard => None,
+            Behaviour::Wander => {
+                if rng.gen_bool(0.5) {
+                    return Plan::Stay;
+                }
+                let (dx, dy) = STEPS[rng.gen_range(0..STEPS.len())];
+                step(enemy.position, dx, dy).filter(free)
+            }
+            Behaviour::Patrol => {
+                let ahead = step(enemy.position, enemy.heading, 0);
+                if ahead == Some(player) {
+                    return Plan::Attack;
+                }
+                if ahead.filter(free).is_none() {
+                    // Turn around at walls and other enemies and try the other way
+                    enemy.heading = -enemy.heading;
+                }
+                step(enemy.position, enemy.heading, 0).filter(free)
+            }
+            Behaviour::Chase => path_toward(enemy.position, player, occupied),
+        };
+        match target {
+            Some(target) => Plan::MoveTo(target),
+            None => Plan::Stay,
+        }
+    }
+}
+
+fn is_adjacent(a: Position, b: Position) -> bool {
+    a.x.abs_diff(b.x) + a.y.abs_diff(b.y) == 1
+}
+
+/// The floor tile next to `from` in direction (`dx`, `dy`), if it isn't a wall.
+fn step(from: Position, dx: i8, dy: i8) -> Option<Position> {
+    let x = from.x.checked_add_signed(dx)?;
+    let y = from.y.checked_add_signed(dy)?;
+    let in_room = (MIN_X..=MAX_X).contains(&x) && (MIN_Y..=MAX_Y).contains(&y);
+    in_room.then_some(Position { x, y })
+}
+
+/// First step of a shortest path from `from` to any free tile next to `player`, found with a
+/// breadth-first search over the room floor.
+fn path_toward(from: Position, player: Position, occupied: &[Position]) -> Option<Position> {
+    if is_adjacent(from, player) {
+        return None;
+    }
+    let width = (MAX_X - MIN_X + 1) as usize;
+    let index =
+        |position: Position| (position.y - MIN_Y) as usize * width + (position.x - MIN_X) as usize;
+    // For every visited tile, the first step taken to reach it
+    let mut first_steps: Vec<Option<Position>> = vec![None; width * (MAX_Y - MIN_Y + 1) as usize];
+    let mut queue = VecDeque::new();
+    for (dx, dy) in STEPS {
+        if let Some(next) = step(from, dx, dy).filter(|next| !occupied.contains(next)) {
+            first_steps[index(next)] = Some(next);
+            queue.push_back(next);
+        }
+    }
+    while let Some(current) = queue.pop_front() {
+        if is_adjacent(current, player) {
+            return first_steps[index(current)];
+        }
+        for (dx, dy) in STEPS {
+            let Some(next) = step(current, dx, dy) else {
+                continue;
+            };
+            if next == from || occupied.contains(&next) || first_steps[index(next)].is_some() {
+                continue;
+            }
+            first_steps[index(next)] = first_steps[index(current)];
+            queue.push_back(next);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::Door;
+    use crate::enemy::EnemyKind;
+    use crate::room::RoomPosition;
+
+    #[test]
+    fn chasers_walk_around_other_enemies() {
+        let from = Position { x: 3, y: 4 };
+        let player = Position { x: 6, y: 4 };
+        let occupied = [Position { x: 4, y: 4 }, player];
+        let next = path_toward(from, player, &occupied).unwrap();
+        assert!(next == Position { x: 3, y: 3 } || next == Position { x: 3, y: 5 });
+    }
+
+    #[test]
+    fn chasers_stop_next_to_the_player() {
+        let player = Position { x: 6, y: 4 };
+        assert_eq!(
+            path_toward(Position { x: 5, y: 4 }, player, &[player]),
+            None
+        );
+    }
+
+    #[test]
+    fn enemies_make_way_for_the_player() {
+        let entry = Position { x: 2, y: 3 };
+        let mut room = Room::new(RoomPosition { x: 0, y: 0 }, vec![Door::LEFT]);
+        room.enemies = vec![
+            Enemy::new(EnemyKind::Slime, 1, entry),
+            Enemy::new(EnemyKind::Slime, 1, Position { x: 2, y: 2 }),
+        ];
+        EnemyAi::make_way(&mut room, entry);
+        let moved = room.enemies[0].position;
+        assert!(is_adjacent(moved, entry));
+        assert_ne!(moved, room.enemies[1].position);
+    }
+
+    #[test]
+    fn steps_stay_inside_the_walls() {
+        assert_eq!(step(Position { x: MAX_X, y: 3 }, 1, 0), None);
+        assert_eq!(step(Position { x: 4, y: MIN_Y }, 0, -1), None);
+        assert_eq!(
+            step(Position { x: 4, y: 3 }, -1, 0),
+            Some(Position { x: 3, y: 3 })
+        );
+    }
+}
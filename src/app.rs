@@ -0,0 +1,121 @@
+//! The terminal game loop: keys in, actions into the game, frames out.
+
+use super::error::{RenderContext, Result};
+use super::game::Game;
+use super::input::InputSource;
+use super::keymap::{Action, KeyMap};
+use super::replay::ReplayInput;
+use super::save::{save_game, DEFAULT_SAVE_FILE};
+use super::ui::hud::Hud;
+use super::ui::minimap::Minimap;
+use super::ui::renderer::Renderer;
+use super::ui::surface::Surface;
+use std::path::Path;
+use termion::event::Key;
+
+/// Whether the game loop goes on after a key.
+#[derive(PartialEq, Debug)]
+pub enum Flow {
+    /// Keep reading keys.
+    Continue,
+    /// Leave the game.
+    Quit,
+}
+
+/// A game played in the terminal, with its key bindings and renderer.
+pub struct App {
+    /// The run being played.
+    pub game: Game,
+    /// Bindings the keys are read with.
+    pub keymap: KeyMap,
+    /// Redraw the minimap after every action.
+    pub debug: bool,
+    /// Draws the game and its events.
+    pub renderer: Renderer,
+}
+
+impl App {
+    /// Wraps `game` for play, nothing is drawn until `draw_screen`.
+    pub fn new(game: Game, keymap: KeyMap, debug: bool) -> App {
+        App {
+            game,
+            keymap,
+            debug,
+            renderer: Renderer::new(),
+        }
+    }
+
+    /// Draws everything from scratch.
+    pub fn draw_screen(&mut self, surface: &mut dyn Surface) -> Result<()> {
+        self.renderer.draw_screen(&self.game, surface)
+    }
+
+    /// Plays the action bound to `key`, ignoring unbound keys.
+    pub fn handle_key(&mut self, key: Key, surface: &mut dyn Surface) -> Result<Flow> {
+        match self.keymap.action(key) {
+            Some(action) => self.handle_action(action, surface),
+            None => Ok(Flow::Continue),
+        }
+    }
+
+    /// Plays `action`, handling the ones outside the game itself such as saving.
+    pub fn handle_action(&mut self, action: Action, surface: &mut dyn Surface) -> Result<Flow> {
+        let game = &mut self.game;
+        match action {
+            Action::Quit => return Ok(Flow::Quit),
+            // Saving and the map are only offered while exploring
+            Action::Save if game.is_exploring() => {
+                let notice = match save_game(game, Path::new(DEFAULT_SAVE_FILE)) {
+                    Ok(()) => format!("Saved to {DEFAULT_SAVE_FILE}"),
+                    Err(err) => format!("Save failed: {err}"),
+                };
+                Renderer::draw_notice(game, &notice, surface)?;
+            }
+            Action::OpenMap if game.is_exploring() => {
+                Minimap::print_minimap(&game.rooms, game, surface)?
+            }
+            Action::Save | Action::OpenMap => return Ok(Flow::Continue),
+            _ => {
+                let Some(action) = action.game_action() else {
+                    return Ok(Flow::Continue);
+                };
+                let events = game.apply(action);
+                self.renderer.render(game, &events, surface)?;
+            }
+        }
+        Hud::draw(game, surface)?;
+        if self.debug {
+            Minimap::print_minimap(&game.rooms, game, surface)?;
+        }
+        surface.flush().drawing("the screen")?;
+        Ok(Flow::Continue)
+    }
+
+    /// Plays recorded actions until the recording runs out or quits. Recorded saves are
+    /// skipped, watching a replay must not overwrite the player's save file.
+    pub fn replay(&mut self, input: &mut ReplayInput, surface: &mut dyn Surface) -> Result<Flow> {
+        while let Some(action) = input.next_action() {
+            if action == Action::Save {
+                if self.game.is_exploring() {
+                    Renderer::draw_notice(&self.game, "Not saved during a replay", surface)?;
+                    surface.flush().drawing("a notice")?;
+                }
+                continue;
+            }
+            if self.handle_action(action, surface)? == Flow::Quit {
+                return Ok(Flow::Quit);
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Feeds keys to an already drawn game until the input runs out or the player quits.
+    pub fn play(&mut self, input: &mut dyn InputSource, surface: &mut dyn Surface) -> Result<Flow> {
+        while let Some(key) = input.next_key()? {
+            if self.handle_key(key, surface)? == Flow::Quit {
+                return Ok(Flow::Quit);
+            }
+        }
+        Ok(Flow::Continue)
+    }
+}
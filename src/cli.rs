@@ -0,0 +1,210 @@
+//! Command line options and the subcommands they select.
+
+use super::config::DEFAULT_BALANCE_FILE;
+use super::difficulty::Difficulty;
+use super::keymap::DEFAULT_KEYMAP_FILE;
+use super::replay::MIN_SPEED;
+use std::path::PathBuf;
+use std::process;
+
+const USAGE: &str = "Usage: adventure [COMMAND] [OPTIONS]
+
+Commands:
+  play                  Play in the terminal, the default
+  script <FILE>         Play a key script without a terminal and print the final screen
+  check                 Load the balance config, keymap and save and report problems
+  help                  Show this help
+
+Options:
+  --seed <N>            Start from seed N instead of a random one
+  --difficulty <LEVEL>  easy, normal or hard
+  --config <FILE>       Balance config, BALANCE_FILE when present
+  --keymap <FILE>       Key bindings, KEYMAP_FILE when present
+  --load <FILE>         Continue a saved game
+  --record <FILE>       Record the actions of the session to FILE
+  --replay <FILE>       Play a recording back, then hand over the controls
+  --speed <X>           Replay speed factor, 1 by default
+  --debug               Redraw the minimap after every action
+  --no-color            Draw everything in the terminal's default color
+  -h, --help            Show this help";
+
+/// What the program was asked to do.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Command {
+    /// Play in the terminal.
+    Play,
+    /// Play the key script at the path headless and print the final screen.
+    Script(PathBuf),
+    /// Load every file the options name and report the first problem.
+    Check,
+    /// Print the usage.
+    Help,
+}
+
+/// Everything the command line can set, with the defaults of a plain `adventure`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Options {
+    /// What to do.
+    pub command: Command,
+    /// Seed of a new run, random if not given.
+    pub seed: Option<u64>,
+    /// Save file to continue from.
+    pub load: Option<PathBuf>,
+    /// File to record the session to.
+    pub record: Option<PathBuf>,
+    /// Recording to play back before handing over the controls.
+    pub replay: Option<PathBuf>,
+    /// Replay speed factor.
+    pub speed: f32,
+    /// Keymap file to load instead of the default one.
+    pub keymap: Option<PathBuf>,
+    /// Balance file to load instead of the default one.
+    pub config: Option<PathBuf>,
+    /// Replaces the difficulty of the balance file.
+    pub difficulty: Option<Difficulty>,
+    /// Redraw the minimap after every action.
+    pub debug: bool,
+    /// Draw in colors, off with `--no-color`.
+    pub color: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            command: Command::Play,
+            seed: None,
+            load: None,
+            record: None,
+            replay: None,
+            speed: 1.0,
+            keymap: None,
+            config: None,
+            difficulty: None,
+            debug: false,
+            color: true,
+        }
+    }
+}
+
+impl Options {
+    /// Parses the process arguments, exiting with the usage on mistakes.
+    pub fn parse() -> Options {
+        Options::from_args(std::env::args().skip(1)).unwrap_or_else(|message| {
+            eprintln!("{message}\n\n{}", Options::usage());
+            process::exit(2);
+        })
+    }
+
+    /// The help text, with the default file names filled in.
+    pub fn usage() -> String {
+        USAGE
+            .replace("BALANCE_FILE", DEFAULT_BALANCE_FILE)
+            .replace("KEYMAP_FILE", DEFAULT_KEYMAP_FILE)
+    }
+
+    /// Parses `args` without the program name, returning a message on mistakes.
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut args = args.peekable();
+        match args.peek().map(String::as_str) {
+            Some("play") => {
+                args.next();
+            }
+            Some("script") => {
+                args.next();
+                let path = args.next().ok_or("script needs a key script file")?;
+                options.command = Command::Script(PathBuf::from(path));
+            }
+            Some("check") => {
+                args.next();
+                options.command = Command::Check;
+            }
+            Some("help") => {
+                args.next();
+                options.command = Command::Help;
+            }
+            _ => {}
+        }
+
+        while let Some(arg) = args.next() {
+            let mut value = |what: &str| args.next().ok_or(format!("{arg} needs {what}"));
+            match arg.as_str() {
+                "--seed" => {
+                    let seed = value("a value")?
+                        .parse::<u64>()
+                        .map_err(|_| "--seed must be a positive number")?;
+                    options.seed = Some(seed);
+                }
+                "--load" => options.load = Some(PathBuf::from(value("a file")?)),
+                "--record" => options.record = Some(PathBuf::from(value("a file")?)),
+                "--replay" => options.replay = Some(PathBuf::from(value("a file")?)),
+                "--speed" => {
+                    options.speed = value("a value")?
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|speed| *speed >= MIN_SPEED)
+                        .ok_or(format!("--speed must be at least {MIN_SPEED}"))?;
+                }
+                "--keymap" => options.keymap = Some(PathBuf::from(value("a file")?)),
+                "--config" => options.config = Some(PathBuf::from(value("a file")?)),
+                "--difficulty" => {
+                    let difficulty = Difficulty::parse(&value("a value")?)
+                        .ok_or("--difficulty must be easy, normal or hard")?;
+                    options.difficulty = Some(difficulty);
+                }
+                "--debug" => options.debug = true,
+                "--no-color" => options.color = false,
+                "-h" | "--help" => options.command = Command::Help,
+                _ => return Err(format!("Unknown argument {arg}")),
+            }
+        }
+        // Recordings always start from a fresh seed, a loaded save would not replay the same way
+        if options.load.is_some() && (options.record.is_some() || options.replay.is_some()) {
+            return Err(String::from(
+                "--load can't be combined with --record or --replay",
+            ));
+        }
+        if options.record.is_some() && options.replay.is_some() {
+            return Err(String::from("--record can't be combined with --replay"));
+        }
+        let recorded = options.record.is_some() || options.replay.is_some();
+        if recorded && options.command != Command::Play {
+            return Err(String::from("--record and --replay only work with play"));
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Options, String> {
+        Options::from_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn subcommands_come_first() {
+        let options = parse("script tests/scripts/fight.keys --seed 59 --debug").unwrap();
+        assert_eq!(
+            options.command,
+            Command::Script(PathBuf::from("tests/scripts/fight.keys"))
+        );
+        assert_eq!(options.seed, Some(59));
+        assert!(options.debug);
+        assert_eq!(parse("--no-color").unwrap().command, Command::Play);
+        assert!(!parse("play --no-color").unwrap().color);
+        assert_eq!(parse("check --help").unwrap().command, Command::Help);
+        assert!(parse("--seed 1 check").is_err());
+    }
+
+    #[test]
+    fn conflicting_options_are_rejected() {
+        assert_eq!(parse("--seed").unwrap_err(), "--seed needs a value");
+        assert!(parse("--record a.replay --replay b.replay").is_err());
+        assert!(parse("script fight.keys --replay b.replay").is_err());
+        assert!(parse("--speed 0").is_err());
+        assert!(parse("--speed 1e-30").is_err());
+        assert!(parse("--speed NaN").is_err());
+    }
+}
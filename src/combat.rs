@@ -0,0 +1,270 @@
+//! Turn-based fights between the player and one enemy.
+
+use super::config::Balance;
+use super::enemy::{Enemy, EnemyTrait};
+use super::event::GameEvent;
+use super::player::Player;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// How many of the latest log lines the combat dialog shows.
+pub const LOG_LINES: usize = 4;
+
+/// What the player does in a combat round.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CombatAction {
+    /// Hit the enemy.
+    Attack,
+    /// Double the player's defense for the enemy's next turn.
+    Defend,
+    /// Drink a potion.
+    UseItem,
+    /// Try to get away.
+    Flee,
+}
+
+/// How a combat round left the fight.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CombatOutcome {
+    /// Neither side is down yet.
+    Ongoing,
+    /// The enemy is down.
+    Victory,
+    /// The player is down.
+    Defeat,
+    /// The player got away.
+    Escaped,
+}
+
+/// A fight against one enemy, played out in rounds until one side drops or the player escapes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Combat {
+    /// The enemy, with its health as the fight left it.
+    pub enemy: Enemy,
+    /// Rounds played so far, the opening shot not counted.
+    pub round: u16,
+    /// What happened, oldest first.
+    pub log: Vec<String>,
+}
+
+impl Combat {
+    /// A fight against `enemy` that hasn't started yet.
+    pub fn new(enemy: Enemy) -> Combat {
+        Combat {
+            enemy,
+            round: 0,
+            log: Vec::new(),
+        }
+    }
+
+    /// Chance to land a hit, kept away from the extremes so no fight is decided up front.
+    fn hit_chance(balance: &Balance, attacker_level: u16, defender_level: u16) -> f32 {
+        balance
+            .win_probability_function(attacker_level, defender_level)
+            .clamp(0.1, 0.95)
+    }
+
+    /// Chance the player hits the enemy in a round.
+    pub fn player_hit_chance(&self, player: &Player, balance: &Balance) -> f32 {
+        Combat::hit_chance(balance, player.level, self.enemy.level)
+    }
+
+    /// Chance the enemy hits the player in a round.
+    pub fn enemy_hit_chance(&self, player: &Player, balance: &Balance) -> f32 {
+        Combat::hit_chance(balance, self.enemy.level, player.level)
+    }
+
+    fn roll_damage(attack: u16, defense: u16, rng: &mut impl Rng) -> u16 {
+        let roll = rng.gen_range(attack / 2..=attack);
+        roll.saturating_sub(defense).max(1)
+    }
+
+    /// The lines the combat dialog shows.
+    pub fn latest_log(&self) -> &[String] {
+        &self.log[self.log.len().saturating_sub(LOG_LINES)..]
+    }
+
+    fn log(&mut self, line: String) {
+        self.log.push(line);
+    }
+
+    /// Starts the fight, giving enemies with a first strike their free shot.
+    ///
+    /// Like the other round functions it changes `player` and pushes what happened onto
+    /// `events`, so a bot can play fights out on a copy of the player. Panics on a `balance`
+    /// that fails `Balance::validate`.
+    pub fn open(
+        &mut self,
+        player: &mut Player,
+        balance: &Balance,
+        rng: &mut impl Rng,
+        events: &mut Vec<GameEvent>,
+    ) -> CombatOutcome {
+        if !self.enemy.has_trait(EnemyTrait::FirstStrike) {
+            return CombatOutcome::Ongoing;
+        }
+        self.log(format!("The {} shoots first", self.enemy.kind.name()));
+        self.enemy_turn(player, false, balance, rng, events)
+    }
+
+    /// Plays the player's `action` followed by the enemy's answer.
+    pub fn play_round(
+        &mut self,
+        action: CombatAction,
+        player: &mut Player,
+        balance: &Balance,
+        rng: &mut impl Rng,
+        events: &mut Vec<GameEvent>,
+    ) -> CombatOutcome {
+        let mut defending = false;
+        match action {
+            CombatAction::Attack => {
+                if rng.gen_bool(self.player_hit_chance(player, balance) as f64) {
+                    let damage = Combat::roll_damage(player.attack(), self.enemy.defense(), rng);
+                    self.enemy.health = self.enemy.health.saturating_sub(damage);
+                    events.push(GameEvent::DamageDealt { amount: damage });
+                    self.log(format!(
+                        "You hit the {} for {damage}",
+                        self.enemy.kind.name()
+                    ));
+                } else {
+                    self.log(String::from("You miss"));
+                }
+                if self.enemy.health == 0 {
+                    self.log(format!("The {} falls", self.enemy.kind.name()));
+                    return CombatOutcome::Victory;
+                }
+            }
+            CombatAction::Defend => {
+                defending = true;
+                self.log(String::from("You raise your guard"));
+            }
+            CombatAction::UseItem => {
+                let Some(healed) = player.drink_potion() else {
+                    // Not worth a round, nothing happened
+                    self.log(String::from("You have no potions left"));
+                    return CombatOutcome::Ongoing;
+                };
+                self.log(format!("You drink a potion and heal {healed}"));
+            }
+            CombatAction::Flee => {
+                let flee_probability = self.enemy.flee_probability(balance.flee_probability);
+                if rng.gen_bool(flee_probability as f64) {
+                    self.log(String::from("You got away"));
+                    return CombatOutcome::Escaped;
+                }
+                self.log(String::from("You fail to get away"));
+            }
+        }
+        self.round += 1;
+        self.enemy_turn(player, defending, balance, rng, events)
+    }
+
+    /// The enemy's answer to the player, `defending` doubles the player's defense.
+    pub fn enemy_turn(
+        &mut self,
+        player: &mut Player,
+        defending: bool,
+        balance: &Balance,
+        rng: &mut impl Rng,
+        events: &mut Vec<GameEvent>,
+    ) -> CombatOutcome {
+        let name = self.enemy.kind.name();
+        if self.enemy.has_trait(EnemyTrait::Regenerates)
+            && self.enemy.health < self.enemy.max_health
+        {
+            self.enemy.health += 1;
+            self.log(format!("The {name} regenerates"));
+        }
+        let attacks = if self.enemy.has_trait(EnemyTrait::Enrages) && self.enemy.is_wounded() {
+            2
+        } else {
+            1
+        };
+        for _ in 0..attacks {
+            if !rng.gen_bool(self.enemy_hit_chance(player, balance) as f64) {
+                self.log(format!("The {name} misses"));
+                continue;
+            }
+            let defense = if defending {
+                player.defense() * 2
+            } else {
+                player.defense()
+            };
+            let damage = Combat::roll_damage(self.enemy.attack(), defense, rng);
+            player.decrease_health(damage);
+            events.push(GameEvent::DamageTaken { amount: damage });
+            self.log(format!("The {name} hits you for {damage}"));
+            if player.is_dead() {
+                return CombatOutcome::Defeat;
+            }
+        }
+        CombatOutcome::Ongoing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::Position;
+    use crate::enemy::EnemyKind;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn fight(seed: u64) -> (CombatOutcome, Combat, Player) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let balance = Balance::default();
+        let mut player = Player::new(Position { x: 5, y: 5 });
+        let mut combat = Combat::new(Enemy::new(EnemyKind::Skeleton, 3, Position { x: 5, y: 4 }));
+        let mut events = Vec::new();
+        loop {
+            let outcome = combat.play_round(
+                CombatAction::Attack,
+                &mut player,
+                &balance,
+                &mut rng,
+                &mut events,
+            );
+            if outcome != CombatOutcome::Ongoing {
+                return (outcome, combat, player);
+            }
+        }
+    }
+
+    #[test]
+    fn fights_end_with_one_side_down() {
+        for seed in 0..20 {
+            let (outcome, combat, player) = fight(seed);
+            match outcome {
+                CombatOutcome::Victory => assert_eq!(combat.enemy.health, 0),
+                CombatOutcome::Defeat => assert!(player.is_dead()),
+                _ => panic!("Unexpected outcome {outcome:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_plays_the_same_fight() {
+        assert_eq!(fight(7).1, fight(7).1);
+    }
+
+    #[test]
+    fn missing_potion_costs_no_round() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut player = Player::new(Position { x: 5, y: 5 });
+        player.inventory.bag.clear();
+        let mut combat = Combat::new(Enemy::new(EnemyKind::Slime, 1, Position { x: 5, y: 4 }));
+        let mut events = Vec::new();
+        let outcome = combat.play_round(
+            CombatAction::UseItem,
+            &mut player,
+            &Balance::default(),
+            &mut rng,
+            &mut events,
+        );
+        assert_eq!(outcome, CombatOutcome::Ongoing);
+        assert!(events.is_empty());
+        assert_eq!(combat.round, 0);
+        assert_eq!(player.health(), player.max_health());
+    }
+}
@@ -0,0 +1,312 @@
+//! Balance settings loaded from `balance.toml` and the screen layout.
+
+use super::difficulty::{Curve, Difficulty, DifficultyCurve};
+use serde::Deserialize;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// Balance file read at startup when no other file is given.
+pub const DEFAULT_BALANCE_FILE: &str = "balance.toml";
+
+/// The HUD sits right of the room, the minimap right of the HUD.
+pub(crate) const HUD_COLUMN: u16 = 15;
+pub(crate) const HUD_LINE: u16 = 2;
+pub(crate) const HUD_WIDTH: u16 = 26;
+pub(crate) const MINIMAP_COLUMN: u16 = HUD_COLUMN + HUD_WIDTH + 1;
+/// The dialog sits below the room.
+pub(crate) const DIALOG_LINE: u16 = 9;
+pub(crate) const DIALOG_COLUMN: u16 = 1;
+
+/// Most enemies a room can hold, one per spawn tile left after the key, item and gold that
+/// are placed first.
+const MAX_ENEMIES: u8 = 8 * 3 - 3;
+
+/// Game balance, loaded from a TOML file so it can be tuned without recompiling.
+///
+/// Every field is optional in the file and falls back to the values below, e.g.
+///
+/// ```toml
+/// enemy_dist = [[0, 0.5], [1, 0.5]]
+/// difficulty = "hard"
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Balance {
+    /// Number of extra doors for a room with 1, 2 or 3 free sides, as `[count, weight]` pairs.
+    pub door_dist_1: Vec<(u8, f32)>,
+    /// See `door_dist_1`.
+    pub door_dist_2: Vec<(u8, f32)>,
+    /// See `door_dist_1`.
+    pub door_dist_3: Vec<(u8, f32)>,
+    /// Number of enemies in a new room, as `[count, weight]` pairs.
+    pub enemy_dist: Vec<(u8, f32)>,
+    /// Preset the curves come from unless they are set below.
+    pub difficulty: Difficulty,
+    /// Replace the curves of the difficulty preset when set.
+    pub enemy_level_curve: Option<Curve>,
+    /// See `enemy_level_curve`.
+    pub win_probability_curve: Option<Curve>,
+    /// Chance to get away when fleeing a fight.
+    pub flee_probability: f32,
+    /// XP for beating an enemy, per enemy level.
+    pub xp_per_enemy_level: f32,
+    /// XP needed to get from level `l` to the next is `level_xp_base * l ^ level_xp_growth`.
+    pub level_xp_base: f32,
+    /// See `level_xp_base`.
+    pub level_xp_growth: f32,
+    /// Outside combat the player heals `regen_amount` every `regen_interval` moves.
+    pub regen_interval: u16,
+    /// See `regen_interval`.
+    pub regen_amount: u16,
+    /// Chance for a new room to hold a shrine or fountain instead of enemies.
+    pub healing_room_probability: f32,
+    /// Chance for a new room to have an item lying around.
+    pub item_probability: f32,
+    /// Chance for a new room to have some gold lying around.
+    pub gold_probability: f32,
+    /// Chance for a new room to hold a merchant instead of enemies.
+    pub merchant_room_probability: f32,
+    /// Chance for one of the doors leading on from a new room to be locked, with its key
+    /// somewhere in the room.
+    pub locked_door_probability: f32,
+    /// Chance for a door leading on from a room with enemies to stay sealed until they are
+    /// all defeated.
+    pub sealed_door_probability: f32,
+}
+
+impl Default for Balance {
+    fn default() -> Balance {
+        Balance {
+            door_dist_1: vec![(0, 0.25), (1, 0.75)],
+            door_dist_2: vec![(0, 0.15), (1, 0.35), (2, 0.5)],
+            door_dist_3: vec![(0, 0.1), (1, 0.3), (2, 0.5), (3, 0.1)],
+            enemy_dist: vec![(0, 0.3), (1, 0.4), (2, 0.2), (3, 0.1)],
+            difficulty: Difficulty::Normal,
+            enemy_level_curve: None,
+            win_probability_curve: None,
+            flee_probability: 0.6,
+            xp_per_enemy_level: 10.0,
+            level_xp_base: 20.0,
+            level_xp_growth: 1.5,
+            regen_interval: 10,
+            regen_amount: 2,
+            healing_room_probability: 0.08,
+            item_probability: 0.4,
+            gold_probability: 0.3,
+            merchant_room_probability: 0.06,
+            locked_door_probability: 0.15,
+            sealed_door_probability: 0.2,
+        }
+    }
+}
+
+impl Balance {
+    /// Identifies these exact settings, so a replay can tell it runs on the balance it was
+    /// recorded with. Stable across runs and builds, unlike the std hashers.
+    pub fn fingerprint(&self) -> u64 {
+        // FNV-1a over the debug form, which lists every field
+        format!("{self:?}")
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    /// Refuses to go on with other settings than a run was `done` with, e.g. "recorded" or
+    /// "saved", given the difficulty and fingerprint of those settings. The run would quietly
+    /// go its own way otherwise.
+    pub fn check_matches(
+        &self,
+        difficulty: Difficulty,
+        fingerprint: u64,
+        done: &str,
+    ) -> Result<(), Error> {
+        let mismatch = |message: String| Err(Error::new(ErrorKind::InvalidInput, message));
+        if difficulty != self.difficulty {
+            return mismatch(format!(
+                "{done} on {} difficulty, not {}",
+                difficulty.name(),
+                self.difficulty.name()
+            ));
+        }
+        if fingerprint != self.fingerprint() {
+            return mismatch(format!("{done} with a different balance config"));
+        }
+        Ok(())
+    }
+
+    /// Reads balance settings from TOML and validates them.
+    pub fn parse(content: &str) -> Result<Balance, Error> {
+        let balance: Balance =
+            toml::from_str(content).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        balance.validate()?;
+        Ok(balance)
+    }
+
+    /// Reads a balance file, see [`Balance::parse`].
+    pub fn load(path: &Path) -> Result<Balance, Error> {
+        Balance::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Rejects settings the generators and formulas can't work with.
+    pub fn validate(&self) -> Result<(), Error> {
+        let invalid = |message: String| Err(Error::new(ErrorKind::InvalidData, message));
+        let distributions = [
+            ("door_dist_1", &self.door_dist_1, 1),
+            ("door_dist_2", &self.door_dist_2, 2),
+            ("door_dist_3", &self.door_dist_3, 3),
+            ("enemy_dist", &self.enemy_dist, MAX_ENEMIES),
+        ];
+        for (name, items, max_count) in distributions {
+            if items.is_empty() {
+                return invalid(format!("{name} must not be empty"));
+            }
+            for (count, weight) in items {
+                if !weight.is_finite() || *weight <= 0.0 {
+                    return invalid(format!("{name} weights must be positive, got {weight}"));
+                }
+                if *count > max_count {
+                    return invalid(format!(
+                        "{name} counts can be at most {max_count}, got {count}"
+                    ));
+                }
+            }
+        }
+        if let Some(curve) = &self.enemy_level_curve {
+            curve.validate("enemy_level_curve")?;
+        }
+        if let Some(curve) = &self.win_probability_curve {
+            curve.validate("win_probability_curve")?;
+        }
+        let probabilities = [
+            ("flee_probability", self.flee_probability),
+            ("healing_room_probability", self.healing_room_probability),
+            ("item_probability", self.item_probability),
+            ("gold_probability", self.gold_probability),
+            ("merchant_room_probability", self.merchant_room_probability),
+            ("locked_door_probability", self.locked_door_probability),
+            ("sealed_door_probability", self.sealed_door_probability),
+        ];
+        for (name, probability) in probabilities {
+            if !(0.0..=1.0).contains(&probability) {
+                return invalid(format!("{name} must be between 0 and 1"));
+            }
+        }
+        if self.regen_interval == 0 {
+            return invalid(String::from("regen_interval must be at least 1"));
+        }
+        let xp_settings = [
+            ("xp_per_enemy_level", self.xp_per_enemy_level),
+            ("level_xp_base", self.level_xp_base),
+            ("level_xp_growth", self.level_xp_growth),
+        ];
+        for (name, value) in xp_settings {
+            if !value.is_finite() || value <= 0.0 {
+                return invalid(format!("{name} must be positive, got {value}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// The configured enemy level curve, or the one of the difficulty.
+    pub fn enemy_level_curve(&self) -> Curve {
+        self.enemy_level_curve
+            .clone()
+            .unwrap_or_else(|| self.difficulty.enemy_level_curve())
+    }
+
+    /// The configured win probability curve, or the one of the difficulty.
+    pub fn win_probability_curve(&self) -> Curve {
+        self.win_probability_curve
+            .clone()
+            .unwrap_or_else(|| self.difficulty.win_probability_curve())
+    }
+
+    /// Level of enemies in a room `distance` rooms away from the start, at least 1.
+    pub fn enemy_level_function(&self, distance: u16) -> u16 {
+        let level = self.enemy_level_curve().value(distance as f32).ceil();
+        level.clamp(1.0, u16::MAX as f32) as u16
+    }
+
+    /// Chance to win a fight by the level difference, the base for the hit chances of combat.
+    pub fn win_probability_function(&self, player_level: u16, enemy_level: u16) -> f32 {
+        let difference = player_level as f32 - enemy_level as f32;
+        self.win_probability_curve()
+            .value(difference)
+            .clamp(0.0, 1.0)
+    }
+
+    /// XP for beating an enemy, a quarter more per level it has over the player and a quarter
+    /// less per level below, but always at least 1.
+    pub fn xp_reward(&self, player_level: u16, enemy_level: u16) -> u32 {
+        let gap = enemy_level as f32 - player_level as f32;
+        let scale = (1.0 + 0.25 * gap).clamp(0.1, 2.0);
+        let xp = (self.xp_per_enemy_level * enemy_level as f32 * scale).round();
+        xp.clamp(1.0, u32::MAX as f32) as u32
+    }
+
+    /// XP needed to get from `level` to the next one.
+    pub fn xp_to_next_level(&self, level: u16) -> u32 {
+        let xp = (self.level_xp_base * (level as f32).powf(self.level_xp_growth)).round();
+        xp.clamp(1.0, u32::MAX as f32) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let balance = Balance::parse("difficulty = \"hard\"\n").unwrap();
+        assert_eq!(balance.difficulty, Difficulty::Hard);
+        assert_eq!(balance.door_dist_1, Balance::default().door_dist_1);
+    }
+
+    #[test]
+    fn defaults_match_the_original_curves() {
+        let balance = Balance::default();
+        assert_eq!(balance.enemy_level_function(1), 1);
+        assert_eq!(balance.enemy_level_function(5), 5);
+        assert_eq!(balance.enemy_level_function(6), 8);
+        assert_eq!(balance.win_probability_function(1, 1), 0.5);
+        assert_eq!(balance.win_probability_function(2, 1), 0.75);
+        assert_eq!(balance.win_probability_function(1, 2), 0.0);
+    }
+
+    #[test]
+    fn curve_override_replaces_the_preset() {
+        let balance =
+            Balance::parse("[enemy_level_curve]\nkind = \"stepped\"\nsteps = [[0, 2], [3, 7]]\n")
+                .unwrap();
+        assert_eq!(balance.enemy_level_function(1), 2);
+        assert_eq!(balance.enemy_level_function(4), 7);
+        assert!(Balance::parse(
+            "[win_probability_curve]\nkind = \"logistic\"\nmidpoint = 0\nsteepness = -1\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn xp_grows_with_level_gap_and_level() {
+        let balance = Balance::default();
+        assert_eq!(balance.xp_reward(1, 1), 10);
+        assert!(balance.xp_reward(1, 3) > 3 * balance.xp_reward(1, 1));
+        assert_eq!(balance.xp_reward(20, 1), 1);
+        assert_eq!(balance.xp_to_next_level(1), 20);
+        assert!(balance.xp_to_next_level(4) > 4 * balance.xp_to_next_level(1));
+        assert!(Balance::parse("level_xp_growth = 0\n").is_err());
+    }
+
+    #[test]
+    fn bad_weights_are_rejected() {
+        assert!(Balance::parse("enemy_dist = []\n").is_err());
+        assert!(Balance::parse("door_dist_1 = [[0, 0.0], [1, 1.0]]\n").is_err());
+        assert!(Balance::parse("door_dist_2 = [[3, 1.0]]\n").is_err());
+        assert!(Balance::parse("door_density = 3\n").is_err());
+        // The key, item and gold are placed first and need tiles of their own
+        assert!(Balance::parse("enemy_dist = [[21, 1.0]]\n").is_ok());
+        assert!(Balance::parse("enemy_dist = [[24, 1.0]]\n").is_err());
+    }
+}
@@ -0,0 +1,39 @@
+//! Small shared types: positions on the grid and the doors of a room.
+
+use serde::{Deserialize, Serialize};
+
+/// A side of a room that can have a door in its wall.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Door {
+    /// The wall at the top.
+    TOP,
+    /// The wall on the right.
+    RIGHT,
+    /// The wall at the bottom.
+    BOTTOM,
+    /// The wall on the left.
+    LEFT,
+}
+
+/// Whether a door can be walked through.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoorState {
+    /// Can be walked through.
+    #[default]
+    Open,
+    /// Opens with the key of the same number, which is used up.
+    Locked(u16),
+    /// Opens once every enemy in the room is defeated.
+    Sealed,
+}
+
+/// A tile inside a room, in terminal cells counted from the room's top-left corner.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    /// Column, the walls are at 1 and 12.
+    pub x: u8,
+    /// Row, the walls are at 1 and 7.
+    pub y: u8,
+}
@@ -1,22 +1,23 @@
+//! Moving the player inside a room and through its doors.
+
+use super::ai::EnemyAi;
 use super::consts::Door;
 use super::consts::Position;
-use super::enemy;
 use super::enemy::Enemy;
 use super::game::Game;
 use super::room::RoomPosition;
-use crate::libs::ui::dialog::Dialog;
-use std::io::Stdout;
-use termion::raw::RawTerminal;
 
+/// Turns movement actions into steps, door transitions, fights and shop visits.
 pub struct PlayerController {}
 
 impl PlayerController {
-    pub fn move_up(game: &mut Game, stdout: &mut RawTerminal<Stdout>) {
+    /// Steps up, through the top door when standing in front of it.
+    pub(crate) fn move_up(game: &mut Game) {
         if game.player.position.y == 2
             && game.current_room.doors.contains(&Door::TOP)
             && (4..10).contains(&game.player.position.x)
+            && game.try_door(Door::TOP)
         {
-            PlayerController::handle_no_enemy_collistion(game, stdout);
             // Entering a new room
             let new_position = RoomPosition {
                 x: game.current_room.grid_position.x,
@@ -30,7 +31,6 @@ impl PlayerController {
                     x: game.player.position.x,
                     y: 6,
                 },
-                stdout,
             );
         } else if game.player.position.y > 2 {
             // Moving in the same room
@@ -39,21 +39,25 @@ impl PlayerController {
                 y: game.player.position.y - 1,
             };
             if let Some(enemy) = game.current_room.get_enemy_at_position(new_position) {
-                PlayerController::handle_enemy_collistion(game, enemy, stdout);
+                PlayerController::handle_enemy_collistion(game, enemy);
+                return;
+            }
+            if game.current_room.has_merchant_at(new_position) {
+                game.open_shop();
                 return;
             }
 
-            PlayerController::handle_no_enemy_collistion(game, stdout);
-            game.move_player(0, -1, stdout);
+            PlayerController::step(game, 0, -1);
         }
     }
 
-    pub fn move_right(game: &mut Game, stdout: &mut RawTerminal<Stdout>) {
+    /// Steps right, through the right door when standing in front of it.
+    pub(crate) fn move_right(game: &mut Game) {
         if game.player.position.x == 11
             && game.current_room.doors.contains(&Door::RIGHT)
             && (3..6).contains(&game.player.position.y)
+            && game.try_door(Door::RIGHT)
         {
-            PlayerController::handle_no_enemy_collistion(game, stdout);
             let new_position = RoomPosition {
                 x: game.current_room.grid_position.x + 1,
                 y: game.current_room.grid_position.y,
@@ -65,7 +69,6 @@ impl PlayerController {
                     x: 2,
                     y: game.player.position.y,
                 },
-                stdout,
             );
         } else if game.player.position.x < 11 {
             let new_position = Position {
@@ -73,21 +76,25 @@ impl PlayerController {
                 y: game.player.position.y,
             };
             if let Some(enemy) = game.current_room.get_enemy_at_position(new_position) {
-                PlayerController::handle_enemy_collistion(game, enemy, stdout);
+                PlayerController::handle_enemy_collistion(game, enemy);
+                return;
+            }
+            if game.current_room.has_merchant_at(new_position) {
+                game.open_shop();
                 return;
             }
 
-            PlayerController::handle_no_enemy_collistion(game, stdout);
-            game.move_player(1, 0, stdout);
+            PlayerController::step(game, 1, 0);
         }
     }
 
-    pub fn move_down(game: &mut Game, stdout: &mut RawTerminal<Stdout>) {
+    /// Steps down, through the bottom door when standing in front of it.
+    pub(crate) fn move_down(game: &mut Game) {
         if game.player.position.y == 6
             && game.current_room.doors.contains(&Door::BOTTOM)
             && (4..10).contains(&game.player.position.x)
+            && game.try_door(Door::BOTTOM)
         {
-            PlayerController::handle_no_enemy_collistion(game, stdout);
             let new_position = RoomPosition {
                 x: game.current_room.grid_position.x,
                 y: game.current_room.grid_position.y - 1,
@@ -99,7 +106,6 @@ impl PlayerController {
                     x: game.player.position.x,
                     y: 2,
                 },
-                stdout,
             );
         } else if game.player.position.y < 6 {
             let new_position = Position {
@@ -107,21 +113,25 @@ impl PlayerController {
                 y: game.player.position.y + 1,
             };
             if let Some(enemy) = game.current_room.get_enemy_at_position(new_position) {
-                PlayerController::handle_enemy_collistion(game, enemy, stdout);
+                PlayerController::handle_enemy_collistion(game, enemy);
+                return;
+            }
+            if game.current_room.has_merchant_at(new_position) {
+                game.open_shop();
                 return;
             }
 
-            PlayerController::handle_no_enemy_collistion(game, stdout);
-            game.move_player(0, 1, stdout);
+            PlayerController::step(game, 0, 1);
         }
     }
 
-    pub fn move_left(game: &mut Game, stdout: &mut RawTerminal<Stdout>) {
+    /// Steps left, through the left door when standing in front of it.
+    pub(crate) fn move_left(game: &mut Game) {
         if game.player.position.x == 2
             && game.current_room.doors.contains(&Door::LEFT)
             && (3..6).contains(&game.player.position.y)
+            && game.try_door(Door::LEFT)
         {
-            PlayerController::handle_no_enemy_collistion(game, stdout);
             let new_position = RoomPosition {
                 x: game.current_room.grid_position.x - 1,
                 y: game.current_room.grid_position.y,
@@ -133,7 +143,6 @@ impl PlayerController {
                     x: 11,
                     y: game.player.position.y,
                 },
-                stdout,
             );
         } else if game.player.position.x > 2 {
             let new_position = Position {
@@ -141,23 +150,25 @@ impl PlayerController {
                 y: game.player.position.y,
             };
             if let Some(enemy) = game.current_room.get_enemy_at_position(new_position) {
-                PlayerController::handle_enemy_collistion(game, enemy, stdout);
+                PlayerController::handle_enemy_collistion(game, enemy);
+                return;
+            }
+            if game.current_room.has_merchant_at(new_position) {
+                game.open_shop();
                 return;
             }
 
-            PlayerController::handle_no_enemy_collistion(game, stdout);
-            game.move_player(-1, 0, stdout);
+            PlayerController::step(game, -1, 0);
         }
     }
 
-    fn handle_enemy_collistion(game: &mut Game, enemy: Enemy, stdout: &mut RawTerminal<Stdout>) {
-        Dialog::clear_fight_dialog(&mut game.player, stdout);
-        Dialog::show_fight_dialog(&mut game.player, enemy, stdout);
-        game.fighting_enemy = Some(enemy);
+    /// Moves the player inside the room and lets the enemies answer.
+    fn step(game: &mut Game, dx: i16, dy: i16) {
+        game.move_player(dx, dy);
+        EnemyAi::take_turn(game);
     }
 
-    fn handle_no_enemy_collistion(game: &mut Game, stdout: &mut RawTerminal<Stdout>) {
-        Dialog::clear_fight_dialog(&mut game.player, stdout);
-        game.fighting_enemy = None;
+    fn handle_enemy_collistion(game: &mut Game, enemy: Enemy) {
+        game.open_fight_prompt(enemy);
     }
 }
@@ -0,0 +1,274 @@
+//! Difficulty presets and the curves that scale enemies with depth.
+
+use serde::{Deserialize, Serialize};
+use std::io::{Error, ErrorKind};
+
+/// Maps an input such as a distance or a level difference onto a difficulty value.
+pub trait DifficultyCurve {
+    /// The curve's value at `x`.
+    fn value(&self, x: f32) -> f32;
+}
+
+/// `slope * x + offset`
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Linear {
+    /// Growth per unit of `x`.
+    pub slope: f32,
+    /// Value at zero, 0 if left out.
+    #[serde(default)]
+    pub offset: f32,
+}
+
+impl DifficultyCurve for Linear {
+    fn value(&self, x: f32) -> f32 {
+        self.slope * x + self.offset
+    }
+}
+
+/// `scale * x²`
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Quadratic {
+    /// Factor on `x²`.
+    pub scale: f32,
+}
+
+impl DifficultyCurve for Quadratic {
+    fn value(&self, x: f32) -> f32 {
+        self.scale * x * x
+    }
+}
+
+/// S-shaped curve rising from 0 to `max`, half way at `midpoint`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Logistic {
+    /// Where the curve is at half of `max`.
+    pub midpoint: f32,
+    /// How sharply it rises around the midpoint, must be positive.
+    pub steepness: f32,
+    /// Value it approaches, 1 if left out.
+    #[serde(default = "Logistic::default_max")]
+    pub max: f32,
+}
+
+impl Logistic {
+    fn default_max() -> f32 {
+        1.0
+    }
+}
+
+impl DifficultyCurve for Logistic {
+    fn value(&self, x: f32) -> f32 {
+        self.max / (1.0 + (-self.steepness * (x - self.midpoint)).exp())
+    }
+}
+
+/// `1 - base^x / 2`, so 50% at zero and approaching 100% as `x` grows.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Exponential {
+    /// Between 0 and 1, smaller rises faster.
+    pub base: f32,
+}
+
+impl DifficultyCurve for Exponential {
+    fn value(&self, x: f32) -> f32 {
+        1.0 - self.base.powf(x) / 2.0
+    }
+}
+
+/// Holds the value of the last step whose start is at most `x`, as `[start, value]` pairs.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Stepped {
+    /// Sorted by their start, at least one.
+    pub steps: Vec<(f32, f32)>,
+}
+
+impl DifficultyCurve for Stepped {
+    fn value(&self, x: f32) -> f32 {
+        self.steps
+            .iter()
+            .take_while(|(start, _)| *start <= x)
+            .last()
+            .or(self.steps.first())
+            .map_or(0.0, |(_, value)| *value)
+    }
+}
+
+/// One of the built-in curves, as written in the balance file with a `kind` key, e.g.
+///
+/// ```toml
+/// [enemy_level_curve]
+/// kind = "linear"
+/// slope = 0.5
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Curve {
+    /// See [`Linear`].
+    Linear(Linear),
+    /// See [`Quadratic`].
+    Quadratic(Quadratic),
+    /// See [`Logistic`].
+    Logistic(Logistic),
+    /// See [`Exponential`].
+    Exponential(Exponential),
+    /// See [`Stepped`].
+    Stepped(Stepped),
+}
+
+impl Curve {
+    /// Rejects curves that can't be evaluated sensibly, naming the setting as `name`.
+    pub fn validate(&self, name: &str) -> Result<(), Error> {
+        let invalid = |message: &str| {
+            Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("{name} {message}"),
+            ))
+        };
+        let numbers: Vec<f32> = match self {
+            Curve::Linear(curve) => vec![curve.slope, curve.offset],
+            Curve::Quadratic(curve) => vec![curve.scale],
+            Curve::Logistic(curve) => vec![curve.midpoint, curve.steepness, curve.max],
+            Curve::Exponential(curve) => vec![curve.base],
+            Curve::Stepped(curve) => curve
+                .steps
+                .iter()
+                .flat_map(|(start, value)| [*start, *value])
+                .collect(),
+        };
+        if numbers.iter().any(|number| !number.is_finite()) {
+            return invalid("must only contain finite numbers");
+        }
+        match self {
+            Curve::Logistic(curve) if curve.steepness <= 0.0 => {
+                invalid("steepness must be positive")
+            }
+            Curve::Exponential(curve) if curve.base <= 0.0 || curve.base >= 1.0 => {
+                invalid("base must be between 0 and 1")
+            }
+            Curve::Stepped(curve) if curve.steps.is_empty() => {
+                invalid("must have at least one step")
+            }
+            Curve::Stepped(curve) if curve.steps.windows(2).any(|pair| pair[0].0 >= pair[1].0) => {
+                invalid("steps must be sorted by their start")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl DifficultyCurve for Curve {
+    fn value(&self, x: f32) -> f32 {
+        match self {
+            Curve::Linear(curve) => curve.value(x),
+            Curve::Quadratic(curve) => curve.value(x),
+            Curve::Logistic(curve) => curve.value(x),
+            Curve::Exponential(curve) => curve.value(x),
+            Curve::Stepped(curve) => curve.value(x),
+        }
+    }
+}
+
+/// Presets for how fast enemies level up and how the odds of a fight fall off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    /// Enemies level up slowly.
+    Easy,
+    /// The default.
+    #[default]
+    Normal,
+    /// Enemies level up in big steps.
+    Hard,
+}
+
+impl Difficulty {
+    /// The difficulty called `name` on the command line.
+    pub fn parse(name: &str) -> Option<Difficulty> {
+        match name {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    /// The name used on the command line and in replay headers.
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    /// Enemy level by Manhattan distance of the room from the start.
+    pub fn enemy_level_curve(self) -> Curve {
+        match self {
+            Difficulty::Easy => Curve::Linear(Linear {
+                slope: 0.5,
+                offset: 0.0,
+            }),
+            Difficulty::Normal => Curve::Quadratic(Quadratic { scale: 0.2 }),
+            Difficulty::Hard => Curve::Stepped(Stepped {
+                steps: vec![(0.0, 1.0), (2.0, 3.0), (4.0, 6.0), (6.0, 10.0), (9.0, 16.0)],
+            }),
+        }
+    }
+
+    /// Chance to win by player level minus enemy level.
+    pub fn win_probability_curve(self) -> Curve {
+        match self {
+            Difficulty::Easy => Curve::Logistic(Logistic {
+                midpoint: -1.0,
+                steepness: 1.2,
+                max: 1.0,
+            }),
+            Difficulty::Normal => Curve::Exponential(Exponential { base: 0.5 }),
+            Difficulty::Hard => Curve::Logistic(Logistic {
+                midpoint: 1.0,
+                steepness: 1.0,
+                max: 1.0,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logistic_is_half_way_at_its_midpoint() {
+        let curve = Logistic {
+            midpoint: 2.0,
+            steepness: 1.0,
+            max: 1.0,
+        };
+        assert_eq!(curve.value(2.0), 0.5);
+        assert!(curve.value(-10.0) < 0.01);
+        assert!(curve.value(10.0) > 0.99);
+    }
+
+    #[test]
+    fn stepped_holds_the_last_reached_step() {
+        let curve = Stepped {
+            steps: vec![(0.0, 1.0), (2.0, 3.0), (4.0, 6.0)],
+        };
+        assert_eq!(curve.value(-1.0), 1.0);
+        assert_eq!(curve.value(1.9), 1.0);
+        assert_eq!(curve.value(2.0), 3.0);
+        assert_eq!(curve.value(100.0), 6.0);
+    }
+
+    #[test]
+    fn harder_presets_give_lower_odds() {
+        let odds = |difficulty: Difficulty| difficulty.win_probability_curve().value(0.0);
+        assert!(odds(Difficulty::Easy) > odds(Difficulty::Normal));
+        assert!(odds(Difficulty::Normal) > odds(Difficulty::Hard));
+    }
+}
@@ -0,0 +1,235 @@
+//! Enemies, their kinds and the traits that change how they fight.
+
+use super::consts::Position;
+use serde::{Deserialize, Serialize};
+
+/// The kinds of enemies, each with its own stats, trait and behaviour.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnemyKind {
+    /// Weak, wanders around and regenerates.
+    #[default]
+    Slime,
+    /// Tough, patrols its row and is hard to flee from.
+    Skeleton,
+    /// Stands guard and shoots first.
+    Archer,
+    /// Strong, hunts the player down and enrages when wounded.
+    Boss,
+}
+
+/// Special rules a kind brings into combat.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum EnemyTrait {
+    /// Heals 1 HP at the start of each of its turns.
+    Regenerates,
+    /// Halves the player's chance to flee.
+    Relentless,
+    /// Gets a free shot when the combat starts.
+    FirstStrike,
+    /// Attacks twice per turn once below half health.
+    Enrages,
+}
+
+/// How an enemy moves around the room on its own.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Behaviour {
+    /// Stays where it was placed.
+    Guard,
+    /// Takes a random step now and then.
+    Wander,
+    /// Walks back and forth along its row.
+    Patrol,
+    /// Heads for the player and starts a fight once next to them.
+    Chase,
+}
+
+impl EnemyKind {
+    /// Every kind, weakest first.
+    pub const ALL: [EnemyKind; 4] = [
+        EnemyKind::Slime,
+        EnemyKind::Skeleton,
+        EnemyKind::Archer,
+        EnemyKind::Boss,
+    ];
+
+    /// Name for prompts and notices.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnemyKind::Slime => "slime",
+            EnemyKind::Skeleton => "skeleton",
+            EnemyKind::Archer => "archer",
+            EnemyKind::Boss => "boss",
+        }
+    }
+
+    /// The special rule the kind fights with.
+    pub fn enemy_trait(&self) -> EnemyTrait {
+        match self {
+            EnemyKind::Slime => EnemyTrait::Regenerates,
+            EnemyKind::Skeleton => EnemyTrait::Relentless,
+            EnemyKind::Archer => EnemyTrait::FirstStrike,
+            EnemyKind::Boss => EnemyTrait::Enrages,
+        }
+    }
+
+    /// How the kind moves when not provoked.
+    pub fn behaviour(&self) -> Behaviour {
+        match self {
+            EnemyKind::Slime => Behaviour::Wander,
+            EnemyKind::Skeleton => Behaviour::Patrol,
+            EnemyKind::Archer => Behaviour::Guard,
+            EnemyKind::Boss => Behaviour::Chase,
+        }
+    }
+
+    /// Relative chance to spawn in a room `distance` rooms away from the origin. Slimes thin
+    /// out with distance, archers only show up from 2 rooms away and bosses from 4.
+    pub fn spawn_weight(&self, distance: u16) -> f32 {
+        let distance = distance as f32;
+        match self {
+            EnemyKind::Slime => (6.0 - distance).max(1.0),
+            EnemyKind::Skeleton => 2.0 + distance.min(4.0) * 0.5,
+            EnemyKind::Archer if distance >= 2.0 => 2.0,
+            EnemyKind::Boss if distance >= 4.0 => 0.3,
+            EnemyKind::Archer | EnemyKind::Boss => 0.0,
+        }
+    }
+
+    /// Kinds that can spawn at `distance`, paired with their weights.
+    pub fn spawn_table(distance: u16) -> Vec<(EnemyKind, f32)> {
+        EnemyKind::ALL
+            .iter()
+            .map(|kind| (*kind, kind.spawn_weight(distance)))
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect()
+    }
+}
+
+impl EnemyTrait {
+    /// Hint shown in the fight prompt.
+    pub fn description(&self) -> &'static str {
+        match self {
+            EnemyTrait::Regenerates => "It regenerates every turn",
+            EnemyTrait::Relentless => "It is hard to get away from",
+            EnemyTrait::FirstStrike => "It shoots first",
+            EnemyTrait::Enrages => "It attacks twice when wounded",
+        }
+    }
+}
+
+/// An enemy standing in a room, stronger the further the room is from the origin.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Enemy {
+    /// What the enemy is.
+    pub kind: EnemyKind,
+    /// Scales its health, attack and defense.
+    pub level: u16,
+    /// Tile it stands on.
+    pub position: Position,
+    /// Set once the player answers its fight prompt in any way: by taking the fight, by
+    /// declining it or by fleeing. Provoked enemies chase the player and can't be declined.
+    pub provoked: bool,
+    /// Health left.
+    pub health: u16,
+    /// Health it started with.
+    pub max_health: u16,
+    /// Patrol direction along the row, -1 for left and 1 for right.
+    pub heading: i8,
+}
+
+impl Enemy {
+    /// A fresh, unprovoked enemy with full health.
+    pub fn new(kind: EnemyKind, level: u16, position: Position) -> Enemy {
+        let max_health = match kind {
+            EnemyKind::Slime => 3 + 3 * level,
+            EnemyKind::Skeleton => 4 + 4 * level,
+            EnemyKind::Archer => 3 + 3 * level,
+            EnemyKind::Boss => 10 + 8 * level,
+        };
+        Enemy {
+            kind,
+            level,
+            position,
+            provoked: false,
+            health: max_health,
+            max_health,
+            heading: 1,
+        }
+    }
+
+    /// Damage it deals before the player's defense.
+    pub fn attack(&self) -> u16 {
+        match self.kind {
+            EnemyKind::Slime => 2 + self.level,
+            EnemyKind::Skeleton => 3 + 2 * self.level,
+            EnemyKind::Archer => 4 + 2 * self.level,
+            EnemyKind::Boss => 5 + 3 * self.level,
+        }
+    }
+
+    /// Damage it shrugs off from each hit.
+    pub fn defense(&self) -> u16 {
+        match self.kind {
+            EnemyKind::Slime | EnemyKind::Archer => self.level / 2,
+            EnemyKind::Skeleton => self.level + 1,
+            EnemyKind::Boss => self.level + 2,
+        }
+    }
+
+    /// Whether its kind fights with `enemy_trait`.
+    pub fn has_trait(&self, enemy_trait: EnemyTrait) -> bool {
+        self.kind.enemy_trait() == enemy_trait
+    }
+
+    /// The chance to get away from this enemy given the configured base chance.
+    pub fn flee_probability(&self, base: f32) -> f32 {
+        if self.has_trait(EnemyTrait::Relentless) {
+            base / 2.0
+        } else {
+            base
+        }
+    }
+
+    /// Provoked enemies hunt the player down whatever their kind.
+    pub fn behaviour(&self) -> Behaviour {
+        if self.provoked {
+            Behaviour::Chase
+        } else {
+            self.kind.behaviour()
+        }
+    }
+
+    /// Whether it is below half health.
+    pub fn is_wounded(&self) -> bool {
+        self.health * 2 < self.max_health
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stronger_kinds_only_spawn_far_from_origin() {
+        let near: Vec<EnemyKind> = EnemyKind::spawn_table(0)
+            .into_iter()
+            .map(|(kind, _)| kind)
+            .collect();
+        assert_eq!(near, vec![EnemyKind::Slime, EnemyKind::Skeleton]);
+        assert_eq!(EnemyKind::spawn_table(4).len(), EnemyKind::ALL.len());
+    }
+
+    #[test]
+    fn skeletons_halve_the_flee_chance() {
+        let position = Position { x: 5, y: 4 };
+        assert_eq!(
+            Enemy::new(EnemyKind::Skeleton, 1, position).flee_probability(0.6),
+            0.3
+        );
+        assert_eq!(
+            Enemy::new(EnemyKind::Slime, 1, position).flee_probability(0.6),
+            0.6
+        );
+    }
+}
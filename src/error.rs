@@ -0,0 +1,117 @@
+//! The crate's error type and helpers to attach context to IO errors.
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Everything that can stop the game.
+#[derive(Debug)]
+pub enum AdventureError {
+    /// Writing to the screen failed while drawing `what`.
+    Render {
+        /// What was being drawn, e.g. "the room".
+        what: &'static str,
+        /// The error of the surface.
+        source: io::Error,
+    },
+    /// Setting up the terminal session failed.
+    Terminal(io::Error),
+    /// Reading keys from the terminal failed.
+    Input(io::Error),
+    /// A balance, keymap, save or replay file could not be read or written.
+    File {
+        /// What was being done, e.g. "load".
+        action: &'static str,
+        /// The file it was done to.
+        path: PathBuf,
+        /// The error of the file system or the parser.
+        source: io::Error,
+    },
+    /// The balance settings a game was started with can't be played, see `Balance::validate`.
+    InvalidBalance(io::Error),
+    /// A save file was read but holds something the game can't resume from.
+    InvalidSave {
+        /// The save file.
+        path: PathBuf,
+        /// What is wrong with it.
+        reason: String,
+    },
+}
+
+/// Result of anything that can stop the game.
+pub type Result<T> = std::result::Result<T, AdventureError>;
+
+impl AdventureError {
+    /// Wraps an error from reading or writing `path`, `action` says what was being done.
+    pub fn file(action: &'static str, path: impl Into<PathBuf>) -> impl FnOnce(io::Error) -> Self {
+        let path = path.into();
+        move |source| AdventureError::File {
+            action,
+            path,
+            source,
+        }
+    }
+}
+
+impl fmt::Display for AdventureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdventureError::Render { what, source } => write!(f, "Failed to draw {what}: {source}"),
+            AdventureError::Terminal(source) => {
+                write!(f, "Failed to set up the terminal: {source}")
+            }
+            AdventureError::Input(source) => write!(f, "Failed to read input: {source}"),
+            AdventureError::File {
+                action,
+                path,
+                source,
+            } => write!(f, "Failed to {action} {}: {source}", path.display()),
+            AdventureError::InvalidBalance(source) => write!(f, "Invalid balance: {source}"),
+            AdventureError::InvalidSave { path, reason } => {
+                write!(f, "Invalid save file {}: {reason}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for AdventureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AdventureError::Render { source, .. }
+            | AdventureError::Terminal(source)
+            | AdventureError::Input(source)
+            | AdventureError::InvalidBalance(source)
+            | AdventureError::File { source, .. } => Some(source),
+            AdventureError::InvalidSave { .. } => None,
+        }
+    }
+}
+
+/// Turns the `io::Result` of a surface call into an `AdventureError` naming what was drawn.
+pub trait RenderContext<T> {
+    /// Names `what` was being drawn when the call failed.
+    fn drawing(self, what: &'static str) -> Result<T>;
+}
+
+impl<T> RenderContext<T> for io::Result<T> {
+    fn drawing(self, what: &'static str) -> Result<T> {
+        self.map_err(|source| AdventureError::Render { what, source })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_say_what_failed() {
+        let failed: io::Result<()> = Err(io::Error::other("broken pipe"));
+        let err = failed.drawing("the right door").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to draw the right door: broken pipe"
+        );
+        let err = AdventureError::file("load", "adventure.sav")(io::Error::other("missing"));
+        assert_eq!(err.to_string(), "Failed to load adventure.sav: missing");
+    }
+}
@@ -0,0 +1,199 @@
+//! What a [`GameAction`](crate::GameAction) caused, reported by
+//! [`Game::apply`](crate::Game::apply).
+
+use super::consts::{Door, Position};
+use super::enemy::Enemy;
+use super::inventory::{Item, Loot};
+use super::room::{HealingKind, RoomPosition};
+use super::shop::ShopMode;
+
+/// Why a merchant turned down a trade.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TradeRefusal {
+    /// The player doesn't have the gold.
+    TooExpensive,
+    /// The bought item wouldn't fit in the bag.
+    BagFull,
+}
+
+/// Something that happened in the game, reported in the order it happened.
+///
+/// The game only changes its own state and records these. Frontends draw from them, so the
+/// terminal, a replay viewer and a headless bot all see the same run.
+#[derive(Clone, PartialEq, Debug)]
+pub enum GameEvent {
+    /// The player took a step inside the room.
+    PlayerMoved {
+        /// Tile they left.
+        from: Position,
+        /// Tile they stand on now.
+        to: Position,
+    },
+    /// The player walked through a door, `discovered` when the room was generated just now.
+    RoomEntered {
+        /// Grid position of the room.
+        position: RoomPosition,
+        /// Whether this was the first visit.
+        discovered: bool,
+    },
+    /// The player bumped into a locked door without its key.
+    DoorLocked {
+        /// The door they bumped into.
+        door: Door,
+        /// Number of the key it needs.
+        key: u16,
+    },
+    /// The player opened a locked door with the key from their bag.
+    DoorUnlocked {
+        /// The door that opened.
+        door: Door,
+        /// Number of the key that was used up.
+        key: u16,
+    },
+    /// The player bumped into a door that stays sealed while enemies are around.
+    DoorSealed {
+        /// The door they bumped into.
+        door: Door,
+    },
+    /// Clearing the room opened its sealed doors.
+    DoorsUnsealed,
+    /// The player stepped onto a healing spot and got their health back.
+    HealingSpotUsed {
+        /// Shrine or fountain.
+        kind: HealingKind,
+    },
+    /// The player picked up what lay on the tile they stepped on.
+    LootPickedUp {
+        /// What they picked up.
+        loot: Loot,
+    },
+    /// The bag was too full to pick up `loot`, which stays on the floor.
+    LootLeftBehind {
+        /// What stays on the floor.
+        loot: Loot,
+    },
+    /// An enemy took a step, `enemy` is where it ended up.
+    EnemyMoved {
+        /// The enemy after its step.
+        enemy: Enemy,
+        /// Tile it left.
+        from: Position,
+    },
+    /// The fight prompt opened for `enemy`.
+    FightOffered {
+        /// The enemy the player ran into.
+        enemy: Enemy,
+    },
+    /// The player walked away from the fight on offer.
+    FightDeclined {
+        /// The enemy they left alone.
+        enemy: Enemy,
+    },
+    /// A provoked enemy did not let the player decline.
+    DeclineRefused {
+        /// The enemy that insists.
+        enemy: Enemy,
+    },
+    /// The player took the fight on offer.
+    FightStarted {
+        /// The enemy they fight.
+        enemy: Enemy,
+    },
+    /// The player hit the enemy they are fighting.
+    DamageDealt {
+        /// Health the enemy lost.
+        amount: u16,
+    },
+    /// The enemy hit the player.
+    DamageTaken {
+        /// Health the player lost.
+        amount: u16,
+    },
+    /// The player fled a fight and the enemy stays in the room.
+    Escaped {
+        /// The enemy they got away from.
+        enemy: Enemy,
+    },
+    /// The player beat the enemy, which left the room.
+    FightWon {
+        /// The beaten enemy.
+        enemy: Enemy,
+        /// Experience the victory earned.
+        xp: u32,
+        /// What the enemy dropped, if anything.
+        loot: Option<Loot>,
+    },
+    /// A victory earned the player one or more levels.
+    LevelledUp {
+        /// Level before the victory.
+        from: u16,
+        /// Level after it.
+        to: u16,
+    },
+    /// The player closed the level-up dialog.
+    LevelUpAcknowledged,
+    /// The player's health ran out and the run is over.
+    PlayerDied {
+        /// What killed them, for the summary.
+        cause: String,
+    },
+    /// The inventory opened.
+    InventoryOpened,
+    /// The inventory closed.
+    InventoryClosed,
+    /// The selection of the inventory or shop moved to `index`.
+    SelectionMoved {
+        /// Position of the selected entry in its list.
+        index: usize,
+    },
+    /// The player drank a potion.
+    PotionDrunk {
+        /// Health it gave back.
+        healed: u16,
+    },
+    /// The player put on a weapon or armour from their bag.
+    ItemEquipped {
+        /// The item now equipped.
+        item: Item,
+    },
+    /// The selected item can't be used from the inventory.
+    ItemNotUsable {
+        /// The item the player tried to use.
+        item: Item,
+    },
+    /// The player walked into a merchant and the shop opened.
+    ShopOpened,
+    /// The shop closed.
+    ShopClosed,
+    /// The shop switched between buying and selling.
+    ShopModeSwitched {
+        /// The mode it switched to.
+        mode: ShopMode,
+    },
+    /// The player bought an item from the merchant.
+    ItemBought {
+        /// What they bought.
+        item: Item,
+        /// Gold it cost.
+        price: u32,
+    },
+    /// The player sold an item to the merchant.
+    ItemSold {
+        /// What they sold.
+        item: Item,
+        /// Gold it earned.
+        price: u32,
+    },
+    /// The merchant turned down a trade.
+    TradeRefused {
+        /// The item the player wanted to trade.
+        item: Item,
+        /// Why it didn't go through.
+        reason: TradeRefusal,
+    },
+    /// A new run started, from the same seed or a fresh one.
+    GameRestarted {
+        /// Seed of the new run.
+        seed: u64,
+    },
+}
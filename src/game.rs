@@ -0,0 +1,851 @@
+//! The simulation: a [`Game`] changes state through [`GameAction`]s and reports what happened
+//! as [`GameEvent`]s, without drawing anything.
+
+use super::ai::EnemyAi;
+use super::combat::{Combat, CombatAction, CombatOutcome};
+use super::config::Balance;
+use super::consts::{Door, DoorState, Position};
+use super::controller::PlayerController;
+use super::enemy::Enemy;
+use super::error::{AdventureError, Result};
+use super::event::{GameEvent, TradeRefusal};
+use super::inventory::{Item, ItemKind, Pickup, BAG_SIZE};
+use super::loot::roll_loot;
+use super::player::Player;
+use super::room::{Room, RoomPosition};
+use super::shop::{Shop, ShopMode};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Figures for the game-over summary.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct RunStats {
+    /// Enemies beaten in combat, escapes don't count.
+    pub enemies_defeated: u16,
+    /// Highest level the player reached.
+    pub max_level: u16,
+    /// Set when the player dies, e.g. "Slain by a boss on level 4 in round 3".
+    pub cause_of_death: Option<String>,
+}
+
+/// Whether the "do you want to fight" dialog is waiting for an answer.
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum FightPrompt {
+    /// No fight on offer.
+    #[default]
+    Closed,
+    /// Offering a fight against this enemy.
+    Open(Enemy),
+}
+
+/// Levels gained from the last victory, shown until the player acknowledges them.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct LevelUp {
+    /// Level before the victory.
+    pub from: u16,
+    /// Level after it, more than one above `from` for a big win.
+    pub to: u16,
+}
+
+/// What the player asks the game to do. What an action means depends on what the game waits
+/// for, e.g. Up moves the inventory cursor while the inventory is open.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameAction {
+    /// Walk up, or move a list selection up.
+    MoveUp,
+    /// Walk right, or switch between buying and selling.
+    MoveRight,
+    /// Walk down, or move a list selection down.
+    MoveDown,
+    /// Walk left, or switch between buying and selling.
+    MoveLeft,
+    /// Answer yes, use the selected item or attack in combat.
+    Confirm,
+    /// Answer no or leave the open screen.
+    Decline,
+    /// Try to get away from a fight.
+    Flee,
+    /// Hit the enemy in combat.
+    Attack,
+    /// Raise the guard for a round, doubling the defense.
+    Defend,
+    /// Drink a potion in combat.
+    UseItem,
+    /// Open or close the inventory.
+    Inventory,
+}
+
+/// A whole run: the player, every room discovered so far and the RNG everything is rolled
+/// from. Two games started from the same seed play out the same for the same actions.
+pub struct Game {
+    pub(crate) rooms: HashMap<RoomPosition, Room>,
+    pub(crate) player: Player,
+    pub(crate) current_room: Room,
+    pub(crate) fight_prompt: FightPrompt,
+    pub(crate) combat: Option<Combat>,
+    pub(crate) level_up: Option<LevelUp>,
+    /// Selected bag slot while the inventory screen is open.
+    pub(crate) inventory_cursor: Option<usize>,
+    /// The shop screen, while trading with the merchant of the current room.
+    pub(crate) shop: Option<Shop>,
+    pub(crate) seed: u64,
+    pub(crate) rng: ChaCha8Rng,
+    pub(crate) stats: RunStats,
+    pub(crate) balance: Balance,
+    /// Recorded since the last `take_events`.
+    pub(crate) events: Vec<GameEvent>,
+}
+
+impl Game {
+    /// The player, with their stats and inventory.
+    pub fn player(&self) -> &Player {
+        &self.player
+    }
+
+    /// The room the player is in. It is a working copy, `rooms` catches up when the player
+    /// leaves it.
+    pub fn current_room(&self) -> &Room {
+        &self.current_room
+    }
+
+    /// Every room discovered so far by its grid position.
+    pub fn rooms(&self) -> &HashMap<RoomPosition, Room> {
+        &self.rooms
+    }
+
+    /// The fight on offer, if any.
+    pub fn fight_prompt(&self) -> FightPrompt {
+        self.fight_prompt
+    }
+
+    /// The fight being played out, if any.
+    pub fn combat(&self) -> Option<&Combat> {
+        self.combat.as_ref()
+    }
+
+    /// Levels gained from the last victory, until the player acknowledges them.
+    pub fn level_up(&self) -> Option<LevelUp> {
+        self.level_up
+    }
+
+    /// Selected bag slot while the inventory is open.
+    pub fn inventory_cursor(&self) -> Option<usize> {
+        self.inventory_cursor
+    }
+
+    /// Mode and selection of the shop while it is open.
+    pub fn shop(&self) -> Option<Shop> {
+        self.shop
+    }
+
+    /// The seed the run was started from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Figures for the game-over summary.
+    pub fn stats(&self) -> &RunStats {
+        &self.stats
+    }
+
+    /// The balance the run is played with.
+    pub fn balance(&self) -> &Balance {
+        &self.balance
+    }
+
+    /// Starts a new run in the origin room, with every roll derived from `seed`. Refuses a
+    /// `balance` that doesn't pass `Balance::validate`, the generators would panic on it.
+    pub fn start(seed: u64, balance: Balance) -> Result<Game> {
+        balance.validate().map_err(AdventureError::InvalidBalance)?;
+        Ok(Game::new(seed, balance))
+    }
+
+    /// Like `start`, for a balance that is known to be valid.
+    fn new(seed: u64, balance: Balance) -> Game {
+        let current_room = Room::new(
+            RoomPosition { x: 0, y: 0 },
+            vec![Door::TOP, Door::RIGHT, Door::BOTTOM, Door::LEFT],
+        );
+        let mut rooms = HashMap::new();
+        rooms.insert(RoomPosition { x: 0, y: 0 }, current_room.clone());
+        let player = Player::new(Position { x: 5, y: 3 });
+        Game {
+            rooms,
+            stats: RunStats {
+                max_level: player.level,
+                ..RunStats::default()
+            },
+            player,
+            current_room,
+            fight_prompt: FightPrompt::Closed,
+            combat: None,
+            level_up: None,
+            inventory_cursor: None,
+            shop: None,
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            balance,
+            events: Vec::new(),
+        }
+    }
+
+    /// Applies `action` to whatever the game is waiting for and returns what happened.
+    pub fn apply(&mut self, action: GameAction) -> Vec<GameEvent> {
+        if self.is_over() {
+            match action {
+                GameAction::Confirm => self.restart(self.seed),
+                // Derived from the game RNG so recorded sessions restart the same way
+                GameAction::Decline => {
+                    let seed = self.rng.gen();
+                    self.restart(seed);
+                }
+                _ => {}
+            }
+        } else if self.is_leveling_up() {
+            if action == GameAction::Confirm {
+                self.close_level_up();
+            }
+        } else if self.is_in_inventory() {
+            match action {
+                GameAction::MoveUp => self.move_inventory_cursor(-1),
+                GameAction::MoveDown => self.move_inventory_cursor(1),
+                GameAction::Confirm => self.use_selected_item(),
+                GameAction::Inventory | GameAction::Decline => self.close_inventory(),
+                _ => {}
+            }
+        } else if self.is_shopping() {
+            match action {
+                GameAction::MoveUp => self.move_shop_cursor(-1),
+                GameAction::MoveDown => self.move_shop_cursor(1),
+                GameAction::MoveLeft | GameAction::MoveRight => self.toggle_shop_mode(),
+                GameAction::Confirm => self.trade_selected_item(),
+                GameAction::Decline => self.close_shop(),
+                _ => {}
+            }
+        } else if self.is_in_combat() {
+            // Nothing but combat actions until the fight is settled
+            match action {
+                GameAction::Attack | GameAction::Confirm => self.combat_round(CombatAction::Attack),
+                GameAction::Defend => self.combat_round(CombatAction::Defend),
+                GameAction::UseItem => self.combat_round(CombatAction::UseItem),
+                GameAction::Flee => self.combat_round(CombatAction::Flee),
+                _ => {}
+            }
+        } else if self.is_prompting() {
+            // The fight prompt has to be answered before anything else happens
+            match action {
+                GameAction::Confirm => self.fight(),
+                GameAction::Decline => self.decline_fight(),
+                GameAction::Flee => self.flee(),
+                _ => {}
+            }
+        } else {
+            match action {
+                GameAction::MoveUp => PlayerController::move_up(self),
+                GameAction::MoveRight => PlayerController::move_right(self),
+                GameAction::MoveDown => PlayerController::move_down(self),
+                GameAction::MoveLeft => PlayerController::move_left(self),
+                GameAction::Inventory => self.open_inventory(),
+                _ => {}
+            }
+        }
+        self.take_events()
+    }
+
+    /// Hands out the events recorded so far.
+    pub(crate) fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    fn restart(&mut self, seed: u64) {
+        *self = Game::new(seed, self.balance.clone());
+        self.emit(GameEvent::GameRestarted { seed });
+    }
+
+    /// Whether the player is free to walk around, with no dialog waiting for an answer.
+    pub fn is_exploring(&self) -> bool {
+        !(self.is_over()
+            || self.is_leveling_up()
+            || self.is_in_inventory()
+            || self.is_shopping()
+            || self.is_in_combat()
+            || self.is_prompting())
+    }
+
+    /// Moves the player through `entry_door` into the room at `new_position`, generating it on
+    /// the first visit, and places them at `player_new_pos`.
+    pub(crate) fn enter_new_room(
+        &mut self,
+        new_position: RoomPosition,
+        entry_door: Door,
+        player_new_pos: Position,
+    ) {
+        self.save_current_room();
+        let discovered = !self.rooms.contains_key(&new_position);
+        if let Some(room) = self.rooms.get(&new_position) {
+            self.current_room = room.clone();
+        } else {
+            let new_room = Room::create_next_room(
+                new_position,
+                entry_door,
+                &self.rooms,
+                &self.balance,
+                &mut self.rng,
+            );
+            self.rooms.insert(new_position, new_room.clone());
+            self.current_room = new_room;
+        }
+        // Locks only keep people out, the door opens from behind
+        self.current_room.open_door(entry_door);
+        self.player.position.x = player_new_pos.x;
+        self.player.position.y = player_new_pos.y;
+        EnemyAi::make_way(&mut self.current_room, self.player.position);
+        self.player.regenerate(&self.balance);
+        self.emit(GameEvent::RoomEntered {
+            position: new_position,
+            discovered,
+        });
+    }
+
+    /// Whether the player gets through `door` of the current room, unlocking it if they carry
+    /// its key.
+    pub(crate) fn try_door(&mut self, door: Door) -> bool {
+        match self.current_room.door_state(door) {
+            DoorState::Open => true,
+            DoorState::Locked(key) => {
+                if !self.player.inventory.take(Item::Key(key)) {
+                    self.emit(GameEvent::DoorLocked { door, key });
+                    return false;
+                }
+                self.current_room.open_door(door);
+                self.emit(GameEvent::DoorUnlocked { door, key });
+                true
+            }
+            DoorState::Sealed => {
+                self.emit(GameEvent::DoorSealed { door });
+                false
+            }
+        }
+    }
+
+    /// Takes a step inside the current room and uses whatever lies on the new tile.
+    pub(crate) fn move_player(&mut self, dx: i16, dy: i16) {
+        let from = self.player.position;
+        self.player.position.x = (self.player.position.x as i16 + dx) as u8;
+        self.player.position.y = (self.player.position.y as i16 + dy) as u8;
+        self.player.regenerate(&self.balance);
+        self.emit(GameEvent::PlayerMoved {
+            from,
+            to: self.player.position,
+        });
+        self.use_healing_spot();
+        self.pick_up();
+    }
+
+    /// Picks up everything on the player's tile that fits.
+    fn pick_up(&mut self) {
+        while let Some(pickup) = self
+            .current_room
+            .get_pickup_at_position(self.player.position)
+        {
+            let loot = pickup.loot;
+            if !self.player.inventory.add_loot(loot) {
+                self.emit(GameEvent::LootLeftBehind { loot });
+                return;
+            }
+            self.current_room.remove_pickup(pickup.position);
+            self.emit(GameEvent::LootPickedUp { loot });
+        }
+    }
+
+    /// Whether the inventory is open.
+    pub fn is_in_inventory(&self) -> bool {
+        self.inventory_cursor.is_some()
+    }
+
+    pub(crate) fn open_inventory(&mut self) {
+        self.inventory_cursor = Some(0);
+        self.emit(GameEvent::InventoryOpened);
+    }
+
+    pub(crate) fn close_inventory(&mut self) {
+        self.inventory_cursor = None;
+        self.emit(GameEvent::InventoryClosed);
+    }
+
+    /// Moves the selection `delta` slots, wrapping around the ends of the bag.
+    pub(crate) fn move_inventory_cursor(&mut self, delta: isize) {
+        let Some(cursor) = self.inventory_cursor else {
+            return;
+        };
+        let len = self.player.inventory.bag.len();
+        if len == 0 {
+            return;
+        }
+        let index = (cursor as isize + delta).rem_euclid(len as isize) as usize;
+        self.inventory_cursor = Some(index);
+        self.emit(GameEvent::SelectionMoved { index });
+    }
+
+    /// Drinks or equips the selected item.
+    pub(crate) fn use_selected_item(&mut self) {
+        let Some(cursor) = self.inventory_cursor else {
+            return;
+        };
+        let Some(item) = self.player.inventory.bag.get(cursor).copied() else {
+            return;
+        };
+        let event = match item.kind() {
+            ItemKind::Potion => {
+                // The potion is in the bag, so there is always one to drink
+                let healed = self.player.drink_potion().unwrap_or(0);
+                GameEvent::PotionDrunk { healed }
+            }
+            ItemKind::Weapon | ItemKind::Armour => {
+                self.player.inventory.equip(cursor);
+                GameEvent::ItemEquipped { item }
+            }
+            ItemKind::Key => GameEvent::ItemNotUsable { item },
+        };
+        let len = self.player.inventory.bag.len();
+        self.inventory_cursor = Some(cursor.min(len.saturating_sub(1)));
+        self.emit(event);
+    }
+
+    /// Whether the player is trading with a merchant.
+    pub fn is_shopping(&self) -> bool {
+        self.shop.is_some()
+    }
+
+    pub(crate) fn open_shop(&mut self) {
+        if self.current_room.merchant.is_none() {
+            return;
+        }
+        self.shop = Some(Shop::default());
+        self.emit(GameEvent::ShopOpened);
+    }
+
+    pub(crate) fn close_shop(&mut self) {
+        self.shop = None;
+        self.emit(GameEvent::ShopClosed);
+    }
+
+    /// Items listed in the current shop mode, the merchant's stock or what the player can sell.
+    pub fn shop_items(&self) -> Vec<Item> {
+        match (self.shop, &self.current_room.merchant) {
+            (Some(shop), Some(merchant)) if shop.mode == ShopMode::Buy => merchant.stock.clone(),
+            (Some(_), Some(_)) => self
+                .player
+                .inventory
+                .bag
+                .iter()
+                .copied()
+                .filter(Item::sellable)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Switches between buying and selling.
+    pub(crate) fn toggle_shop_mode(&mut self) {
+        let Some(shop) = self.shop else {
+            return;
+        };
+        let mode = match shop.mode {
+            ShopMode::Buy => ShopMode::Sell,
+            ShopMode::Sell => ShopMode::Buy,
+        };
+        self.shop = Some(Shop { mode, cursor: 0 });
+        self.emit(GameEvent::ShopModeSwitched { mode });
+    }
+
+    /// Moves the selection `delta` slots, wrapping around the ends of the list.
+    pub(crate) fn move_shop_cursor(&mut self, delta: isize) {
+        let Some(shop) = self.shop else {
+            return;
+        };
+        let len = self.shop_items().len();
+        if len == 0 {
+            return;
+        }
+        let index = (shop.cursor as isize + delta).rem_euclid(len as isize) as usize;
+        self.shop = Some(Shop {
+            cursor: index,
+            ..shop
+        });
+        self.emit(GameEvent::SelectionMoved { index });
+    }
+
+    /// Buys or sells the selected item.
+    pub(crate) fn trade_selected_item(&mut self) {
+        let Some(shop) = self.shop else {
+            return;
+        };
+        let Some(item) = self.shop_items().get(shop.cursor).copied() else {
+            return;
+        };
+        let Some(merchant) = &self.current_room.merchant else {
+            return;
+        };
+        let inventory = &self.player.inventory;
+        let (buy_price, sell_price) = (merchant.buy_price(item), merchant.sell_price(item));
+        let refusal = if shop.mode == ShopMode::Buy && inventory.gold < buy_price {
+            Some(TradeRefusal::TooExpensive)
+        } else if shop.mode == ShopMode::Buy && inventory.bag.len() >= BAG_SIZE {
+            Some(TradeRefusal::BagFull)
+        } else {
+            None
+        };
+        if let Some(reason) = refusal {
+            self.emit(GameEvent::TradeRefused { item, reason });
+            return;
+        }
+
+        let Some(merchant) = &mut self.current_room.merchant else {
+            return;
+        };
+        let inventory = &mut self.player.inventory;
+        let event = match shop.mode {
+            ShopMode::Buy => {
+                merchant.stock.remove(shop.cursor);
+                inventory.gold -= buy_price;
+                inventory.add(item);
+                GameEvent::ItemBought {
+                    item,
+                    price: buy_price,
+                }
+            }
+            ShopMode::Sell => {
+                // The list skips unsellable items, so the cursor is no index into the bag
+                let Some(index) = inventory.bag.iter().position(|held| *held == item) else {
+                    return;
+                };
+                inventory.bag.remove(index);
+                inventory.gold = inventory.gold.saturating_add(sell_price);
+                merchant.stock.push(item);
+                GameEvent::ItemSold {
+                    item,
+                    price: sell_price,
+                }
+            }
+        };
+        let len = self.shop_items().len();
+        self.shop = Some(Shop {
+            cursor: shop.cursor.min(len.saturating_sub(1)),
+            ..shop
+        });
+        self.emit(event);
+    }
+
+    fn use_healing_spot(&mut self) {
+        let Some(healing) = &mut self.current_room.healing else {
+            return;
+        };
+        if healing.used || healing.position != self.player.position {
+            return;
+        }
+        healing.used = true;
+        let kind = healing.kind;
+        self.player.heal_fully();
+        self.emit(GameEvent::HealingSpotUsed { kind });
+    }
+
+    pub(crate) fn save_current_room(&mut self) {
+        self.rooms
+            .insert(self.current_room.grid_position, self.current_room.clone());
+    }
+
+    /// Whether a fight is on offer and waits for an answer.
+    pub fn is_prompting(&self) -> bool {
+        self.fight_prompt != FightPrompt::Closed
+    }
+
+    pub(crate) fn open_fight_prompt(&mut self, enemy: Enemy) {
+        self.fight_prompt = FightPrompt::Open(enemy);
+        self.emit(GameEvent::FightOffered { enemy });
+    }
+
+    fn close_fight_prompt(&mut self) {
+        self.fight_prompt = FightPrompt::Closed;
+    }
+
+    /// Whether a fight is being played out.
+    pub fn is_in_combat(&self) -> bool {
+        self.combat.is_some()
+    }
+
+    /// Accepts the fight offered by the prompt and starts the first round.
+    pub(crate) fn fight(&mut self) {
+        let FightPrompt::Open(enemy) = self.fight_prompt else {
+            return;
+        };
+        self.close_fight_prompt();
+        let enemy = self.provoke(enemy);
+        self.emit(GameEvent::FightStarted { enemy });
+        let mut combat = Combat::new(enemy);
+        let outcome = combat.open(
+            &mut self.player,
+            &self.balance,
+            &mut self.rng,
+            &mut self.events,
+        );
+        self.combat = Some(combat);
+        self.finish_round(outcome);
+    }
+
+    pub(crate) fn decline_fight(&mut self) {
+        let FightPrompt::Open(enemy) = self.fight_prompt else {
+            return;
+        };
+        if enemy.provoked {
+            self.emit(GameEvent::DeclineRefused { enemy });
+            return;
+        }
+        let enemy = self.provoke(enemy);
+        self.close_fight_prompt();
+        self.emit(GameEvent::FightDeclined { enemy });
+    }
+
+    pub(crate) fn flee(&mut self) {
+        let FightPrompt::Open(enemy) = self.fight_prompt else {
+            return;
+        };
+        let enemy = self.provoke(enemy);
+        self.close_fight_prompt();
+        let flee_probability = enemy.flee_probability(self.balance.flee_probability);
+        if self.rng.gen_bool(flee_probability as f64) {
+            self.emit(GameEvent::Escaped { enemy });
+            return;
+        }
+
+        // A failed escape gives the enemy a free hit and starts the fight
+        self.emit(GameEvent::FightStarted { enemy });
+        let mut combat = Combat::new(enemy);
+        combat.log.push(String::from("You fail to get away"));
+        let outcome = combat.enemy_turn(
+            &mut self.player,
+            false,
+            &self.balance,
+            &mut self.rng,
+            &mut self.events,
+        );
+        self.combat = Some(combat);
+        self.finish_round(outcome);
+    }
+
+    pub(crate) fn combat_round(&mut self, action: CombatAction) {
+        let Some(mut combat) = self.combat.clone() else {
+            return;
+        };
+        let outcome = combat.play_round(
+            action,
+            &mut self.player,
+            &self.balance,
+            &mut self.rng,
+            &mut self.events,
+        );
+        self.current_room.update_enemy(combat.enemy);
+        self.combat = Some(combat);
+        self.finish_round(outcome);
+    }
+
+    fn finish_round(&mut self, outcome: CombatOutcome) {
+        let Some(combat) = self.combat.clone() else {
+            return;
+        };
+        match outcome {
+            CombatOutcome::Ongoing => {}
+            CombatOutcome::Victory => {
+                self.combat = None;
+                self.win(combat.enemy);
+            }
+            CombatOutcome::Escaped => {
+                self.combat = None;
+                self.emit(GameEvent::Escaped {
+                    enemy: combat.enemy,
+                });
+            }
+            CombatOutcome::Defeat => {
+                self.combat = None;
+                let cause = format!(
+                    "Slain by a {} on level {} in round {}",
+                    combat.enemy.kind.name(),
+                    combat.enemy.level,
+                    combat.round
+                );
+                self.stats.cause_of_death = Some(cause.clone());
+                self.emit(GameEvent::PlayerDied { cause });
+            }
+        }
+    }
+
+    /// Marks the enemy in the current room as provoked and returns its updated copy.
+    fn provoke(&mut self, enemy: Enemy) -> Enemy {
+        let provoked = Enemy {
+            provoked: true,
+            ..enemy
+        };
+        self.current_room.update_enemy(provoked);
+        provoked
+    }
+
+    fn win(&mut self, enemy: Enemy) {
+        let from = self.player.level;
+        let xp = self.balance.xp_reward(from, enemy.level);
+        let levels = self.player.gain_xp(xp, &self.balance);
+        self.stats.enemies_defeated += 1;
+        self.stats.max_level = self.stats.max_level.max(self.player.level);
+        self.current_room.remove_enemy(enemy.position);
+        // The loot stays on the enemy's tile until the player walks over it
+        let loot = roll_loot(&enemy, &mut self.rng);
+        if let Some(loot) = loot {
+            self.current_room.pickups.push(Pickup {
+                loot,
+                position: enemy.position,
+            });
+        }
+        self.emit(GameEvent::FightWon { enemy, xp, loot });
+        if self.current_room.enemies.is_empty() && self.current_room.unseal_doors() {
+            self.emit(GameEvent::DoorsUnsealed);
+        }
+        if levels > 0 {
+            let to = self.player.level;
+            self.level_up = Some(LevelUp { from, to });
+            self.emit(GameEvent::LevelledUp { from, to });
+        }
+    }
+
+    /// Whether a level-up waits to be acknowledged.
+    pub fn is_leveling_up(&self) -> bool {
+        self.level_up.is_some()
+    }
+
+    pub(crate) fn close_level_up(&mut self) {
+        if self.level_up.take().is_some() {
+            self.emit(GameEvent::LevelUpAcknowledged);
+        }
+    }
+
+    /// Whether the player died, only restarting is left then.
+    pub fn is_over(&self) -> bool {
+        self.player.is_dead()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shop::Merchant;
+
+    #[test]
+    fn actions_report_what_happened() {
+        let mut game = Game::start(1, Balance::default()).unwrap();
+        let events = game.apply(GameAction::MoveDown);
+        assert_eq!(
+            events.first(),
+            Some(&GameEvent::PlayerMoved {
+                from: Position { x: 5, y: 3 },
+                to: Position { x: 5, y: 4 },
+            })
+        );
+        assert!(game.take_events().is_empty());
+        assert_eq!(
+            game.apply(GameAction::Inventory),
+            vec![GameEvent::InventoryOpened]
+        );
+        assert!(!game.is_exploring());
+    }
+
+    #[test]
+    fn same_actions_replay_the_same_events() {
+        let actions = [
+            GameAction::MoveDown,
+            GameAction::MoveRight,
+            GameAction::MoveRight,
+            GameAction::Confirm,
+            GameAction::Attack,
+        ];
+        let play = || {
+            let mut game = Game::start(7, Balance::default()).unwrap();
+            actions
+                .iter()
+                .flat_map(|action| game.apply(*action))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(play(), play());
+    }
+
+    #[test]
+    fn players_never_share_a_tile_with_an_enemy() {
+        let moves = [
+            GameAction::MoveUp,
+            GameAction::MoveRight,
+            GameAction::MoveDown,
+            GameAction::MoveLeft,
+        ];
+        for seed in 0..100 {
+            let mut game = Game::start(seed, Balance::default()).unwrap();
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            for _ in 0..300 {
+                // Walk around and decline or flee every fight to roam through many rooms
+                let action = if game.is_exploring() {
+                    moves[rng.gen_range(0..moves.len())]
+                } else if game.is_prompting() {
+                    GameAction::Decline
+                } else {
+                    GameAction::Flee
+                };
+                game.apply(action);
+                if game.is_prompting() {
+                    game.apply(GameAction::Flee);
+                }
+                let player = game.player.position;
+                assert!(
+                    game.current_room.get_enemy_at_position(player).is_none(),
+                    "Seed {seed} put the player on an enemy at {},{}",
+                    player.x,
+                    player.y
+                );
+                if game.is_over() {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn keys_are_not_for_sale() {
+        let mut game = Game::start(1, Balance::default()).unwrap();
+        game.current_room.merchant = Some(Merchant {
+            position: Position { x: 5, y: 4 },
+            stock: Vec::new(),
+            distance: 0,
+        });
+        game.player.inventory.bag = vec![Item::Key(1), Item::Dagger];
+        game.open_shop();
+        game.toggle_shop_mode();
+        assert_eq!(game.shop_items(), vec![Item::Dagger]);
+
+        game.trade_selected_item();
+        assert_eq!(game.player.inventory.bag, vec![Item::Key(1)]);
+        assert!(game.shop_items().is_empty());
+    }
+
+    #[test]
+    fn unplayable_balances_are_refused() {
+        let balance = Balance {
+            flee_probability: 2.0,
+            ..Balance::default()
+        };
+        assert!(matches!(
+            Game::start(1, balance),
+            Err(AdventureError::InvalidBalance(_))
+        ));
+    }
+}
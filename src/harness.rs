@@ -0,0 +1,184 @@
+//! Drives a seeded game with a key script against an in-memory surface and compares the
+//! final frame with a golden snapshot under `tests/golden`. Run the tests with
+//! `UPDATE_GOLDEN=1` to rewrite the snapshots after an intended change.
+
+use super::app::App;
+use super::config::Balance;
+use super::game::Game;
+use super::input::ScriptInput;
+use super::inventory::Item;
+use super::keymap::KeyMap;
+use super::ui::surface::GridSurface;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+fn test_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests")
+}
+
+/// Replays `tests/scripts/<name>.keys` against a game started from `seed`.
+fn run_script(seed: u64, name: &str) -> (Game, GridSurface) {
+    run_script_with(seed, Balance::default(), name)
+}
+
+fn run_script_with(seed: u64, balance: Balance, name: &str) -> (Game, GridSurface) {
+    let mut app = App::new(Game::start(seed, balance).unwrap(), KeyMap::default(), true);
+    let path = test_dir().join("scripts").join(format!("{name}.keys"));
+    let mut input = ScriptInput::from_file(&path)
+        .unwrap_or_else(|err| panic!("Invalid key script {}: {err}", path.display()));
+    let mut surface = GridSurface::new();
+    app.draw_screen(&mut surface).unwrap();
+    app.play(&mut input, &mut surface).unwrap();
+    (app.game, surface)
+}
+
+fn snapshot(game: &Game, surface: &GridSurface) -> String {
+    let mut state = String::new();
+    let room = &game.current_room;
+    writeln!(state, "{surface}").unwrap();
+    writeln!(state, "---").unwrap();
+    writeln!(
+        state,
+        "room: {},{} doors: {:?}",
+        room.grid_position.x, room.grid_position.y, room.doors
+    )
+    .unwrap();
+    writeln!(
+        state,
+        "player: {},{} level: {} health: {} potions: {} prompt: {:?}",
+        game.player.position.x,
+        game.player.position.y,
+        game.player.level,
+        game.player.health(),
+        game.player.inventory.count(Item::Potion),
+        game.fight_prompt
+    )
+    .unwrap();
+    let inventory = &game.player.inventory;
+    writeln!(
+        state,
+        "bag: {:?} weapon: {:?} armour: {:?} gold: {}",
+        inventory.bag, inventory.weapon, inventory.armour, inventory.gold
+    )
+    .unwrap();
+    if let Some(combat) = &game.combat {
+        writeln!(
+            state,
+            "combat: round {} enemy health: {}",
+            combat.round, combat.enemy.health
+        )
+        .unwrap();
+    }
+    for enemy in &room.enemies {
+        writeln!(
+            state,
+            "enemy: {:?} {},{} level: {} health: {} provoked: {}",
+            enemy.kind,
+            enemy.position.x,
+            enemy.position.y,
+            enemy.level,
+            enemy.health,
+            enemy.provoked
+        )
+        .unwrap();
+    }
+    writeln!(state, "rooms: {}", game.rooms.len()).unwrap();
+    state
+}
+
+fn assert_golden(name: &str, actual: &str) {
+    let path = test_dir().join("golden").join(format!("{name}.txt"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Missing golden file {}", path.display()));
+    assert_eq!(expected, actual, "Frame differs from {}", path.display());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn room_transitions_match_golden() {
+        let (game, surface) = run_script(1, "room_transitions");
+        assert_golden("room_transitions", &snapshot(&game, &surface));
+    }
+
+    #[test]
+    fn fight_matches_golden() {
+        let (game, surface) = run_script(59, "fight");
+        assert_golden("fight", &snapshot(&game, &surface));
+    }
+
+    #[test]
+    fn level_up_matches_golden() {
+        let balance = Balance {
+            level_xp_base: 5.0,
+            ..Balance::default()
+        };
+        let (game, surface) = run_script_with(59, balance, "fight");
+        assert_golden("level_up", &snapshot(&game, &surface));
+    }
+
+    #[test]
+    fn combat_matches_golden() {
+        let (game, surface) = run_script(59, "combat");
+        assert_golden("combat", &snapshot(&game, &surface));
+    }
+
+    #[test]
+    fn fight_prompt_matches_golden() {
+        let (game, surface) = run_script(59, "fight_prompt");
+        assert_golden("fight_prompt", &snapshot(&game, &surface));
+    }
+
+    #[test]
+    fn healing_matches_golden() {
+        let (game, surface) = run_script(33, "healing");
+        assert_golden("healing", &snapshot(&game, &surface));
+    }
+
+    #[test]
+    fn inventory_matches_golden() {
+        let (game, surface) = run_script(2, "inventory");
+        assert_golden("inventory", &snapshot(&game, &surface));
+    }
+
+    #[test]
+    fn shop_matches_golden() {
+        let (game, surface) = run_script(26, "shop");
+        assert_golden("shop", &snapshot(&game, &surface));
+    }
+
+    #[test]
+    fn locked_door_matches_golden() {
+        let (game, surface) = run_script(11, "locked_door");
+        assert_golden("locked_door", &snapshot(&game, &surface));
+    }
+
+    #[test]
+    fn minimap_matches_golden() {
+        let (game, surface) = run_script(5, "minimap");
+        assert_golden("minimap", &snapshot(&game, &surface));
+    }
+
+    #[test]
+    fn same_seed_and_script_give_same_frame() {
+        let (first_game, first_surface) = run_script(42, "minimap");
+        let (second_game, second_surface) = run_script(42, "minimap");
+        assert_eq!(
+            snapshot(&first_game, &first_surface),
+            snapshot(&second_game, &second_surface)
+        );
+    }
+
+    #[test]
+    fn unknown_key_name_is_rejected() {
+        assert!(ScriptInput::parse("Up Sideways").is_err());
+    }
+}
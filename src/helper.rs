@@ -0,0 +1,29 @@
+//! Random helpers shared by the generators.
+
+use super::consts::Position;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use std::ops::Range;
+
+pub fn select_random_weighted<'a, Value>(
+    items: &'a [(Value, f32)],
+    rng: &mut impl Rng,
+) -> &'a Value {
+    let dist = WeightedIndex::new(items.iter().map(|item| item.1)).unwrap();
+    &items[dist.sample(rng)].0
+}
+
+/// Columns and rows things are spawned on, one tile clear of the walls.
+const SPAWN_COLUMNS: Range<u8> = 3..11;
+const SPAWN_ROWS: Range<u8> = 3..6;
+
+pub fn generate_enemy_position(rng: &mut impl Rng) -> Position {
+    let x = rng.gen_range(SPAWN_COLUMNS);
+    let y = rng.gen_range(SPAWN_ROWS);
+    Position { x, y }
+}
+
+/// Every tile `generate_enemy_position` can return.
+pub fn spawn_positions() -> impl Iterator<Item = Position> {
+    SPAWN_ROWS.flat_map(|y| SPAWN_COLUMNS.map(move |x| Position { x, y }))
+}
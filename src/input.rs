@@ -0,0 +1,122 @@
+//! Where keys come from: the terminal, a script or a list in tests.
+
+use super::error::{self, AdventureError};
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{Error, ErrorKind, Stdin};
+use std::path::Path;
+use termion::event::Key;
+use termion::input::{Keys, TermRead};
+
+/// Where the game loop gets its keys from.
+pub trait InputSource {
+    /// Returns the next key to process, or `None` once the input is exhausted.
+    fn next_key(&mut self) -> error::Result<Option<Key>>;
+}
+
+/// Keys typed in the terminal.
+pub struct TerminalInput {
+    keys: Keys<Stdin>,
+}
+
+impl TerminalInput {
+    /// Reads keys from `stdin`, which should be in raw mode.
+    pub fn new(stdin: Stdin) -> TerminalInput {
+        TerminalInput { keys: stdin.keys() }
+    }
+}
+
+impl InputSource for TerminalInput {
+    fn next_key(&mut self) -> error::Result<Option<Key>> {
+        self.keys.next().transpose().map_err(AdventureError::Input)
+    }
+}
+
+/// Replays a fixed list of keys, e.g. a key script loaded from a file.
+pub struct ScriptInput {
+    keys: VecDeque<Key>,
+}
+
+impl ScriptInput {
+    /// Hands out `keys` in order.
+    pub fn new(keys: Vec<Key>) -> ScriptInput {
+        ScriptInput { keys: keys.into() }
+    }
+
+    /// Parses whitespace separated key names, `#` starts a comment until the end of the line.
+    pub fn parse(script: &str) -> Result<ScriptInput, Error> {
+        let mut keys = Vec::new();
+        for line in script.lines() {
+            let line = line.split('#').next().unwrap_or("");
+            for name in line.split_whitespace() {
+                let key = parse_key(name).ok_or_else(|| {
+                    Error::new(ErrorKind::InvalidData, format!("Unknown key {name}"))
+                })?;
+                keys.push(key);
+            }
+        }
+        Ok(ScriptInput::new(keys))
+    }
+
+    /// Reads a key script, see [`ScriptInput::parse`].
+    pub fn from_file(path: &Path) -> Result<ScriptInput, Error> {
+        ScriptInput::parse(&fs::read_to_string(path)?)
+    }
+}
+
+impl InputSource for ScriptInput {
+    fn next_key(&mut self) -> error::Result<Option<Key>> {
+        Ok(self.keys.pop_front())
+    }
+}
+
+/// The key called `name` in scripts and keymaps, like `Up`, `q` or `Ctrl-s`.
+pub fn parse_key(name: &str) -> Option<Key> {
+    let key = match name {
+        "Up" => Key::Up,
+        "Down" => Key::Down,
+        "Left" => Key::Left,
+        "Right" => Key::Right,
+        "Esc" => Key::Esc,
+        "Enter" => Key::Char('\n'),
+        "Space" => Key::Char(' '),
+        "Tab" => Key::Char('\t'),
+        "Backspace" => Key::Backspace,
+        _ => {
+            if let Some(ctrl) = name.strip_prefix("Ctrl-") {
+                let mut chars = ctrl.chars();
+                return match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some(Key::Ctrl(c)),
+                    _ => None,
+                };
+            }
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Key::Char(c),
+                _ => return None,
+            }
+        }
+    };
+    Some(key)
+}
+
+/// The name [`parse_key`] reads back as `key`, `None` for keys without one.
+pub fn key_name(key: Key) -> Option<String> {
+    let name = match key {
+        Key::Up => "Up",
+        Key::Down => "Down",
+        Key::Left => "Left",
+        Key::Right => "Right",
+        Key::Esc => "Esc",
+        Key::Char('\n') => "Enter",
+        Key::Char(' ') => "Space",
+        Key::Char('\t') => "Tab",
+        Key::Backspace => "Backspace",
+        // Would be read back as a comment
+        Key::Char('#') => return None,
+        Key::Char(c) => return Some(c.to_string()),
+        Key::Ctrl(c) => return Some(format!("Ctrl-{c}")),
+        _ => return None,
+    };
+    Some(name.to_string())
+}
@@ -0,0 +1,307 @@
+//! Items, the player's bag and loot lying on the floor.
+
+use super::consts::Position;
+use serde::{Deserialize, Serialize};
+
+/// Most items the bag holds, equipped ones not counted.
+pub const BAG_SIZE: usize = 10;
+/// Health a potion gives back.
+pub const POTION_HEALING: u16 = 30;
+
+/// Something the player can carry.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Item {
+    /// Heals 30 health when drunk.
+    Potion,
+    /// The weakest weapon.
+    Dagger,
+    /// Weapon between the dagger and the axe.
+    Sword,
+    /// The strongest weapon.
+    Axe,
+    /// The weakest armour.
+    LeatherArmour,
+    /// Armour between leather and plate.
+    ChainMail,
+    /// The strongest armour.
+    PlateArmour,
+    /// Opens the locked door with the same number.
+    Key(u16),
+}
+
+/// What an item is used for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ItemKind {
+    /// Drunk for health.
+    Potion,
+    /// Equipped to hit harder.
+    Weapon,
+    /// Equipped to take less damage.
+    Armour,
+    /// Opens a locked door.
+    Key,
+}
+
+impl Item {
+    /// Items that turn up on their own. Keys are only placed next to their doors.
+    pub const ALL: [Item; 7] = [
+        Item::Potion,
+        Item::Dagger,
+        Item::Sword,
+        Item::Axe,
+        Item::LeatherArmour,
+        Item::ChainMail,
+        Item::PlateArmour,
+    ];
+
+    /// What the item is used for.
+    pub fn kind(&self) -> ItemKind {
+        match self {
+            Item::Potion => ItemKind::Potion,
+            Item::Dagger | Item::Sword | Item::Axe => ItemKind::Weapon,
+            Item::LeatherArmour | Item::ChainMail | Item::PlateArmour => ItemKind::Armour,
+            Item::Key(_) => ItemKind::Key,
+        }
+    }
+
+    /// Name without the key number.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Item::Potion => "potion",
+            Item::Dagger => "dagger",
+            Item::Sword => "sword",
+            Item::Axe => "axe",
+            Item::LeatherArmour => "leather armour",
+            Item::ChainMail => "chain mail",
+            Item::PlateArmour => "plate armour",
+            Item::Key(_) => "key",
+        }
+    }
+
+    /// Name that tells keys apart by their number.
+    pub fn label(&self) -> String {
+        match self {
+            Item::Key(number) => format!("key #{number}"),
+            _ => self.name().to_string(),
+        }
+    }
+
+    /// Name with what the item does, as listed in the inventory.
+    pub fn description(&self) -> String {
+        match self.kind() {
+            ItemKind::Potion => format!("{} (heals {POTION_HEALING})", self.name()),
+            ItemKind::Weapon => format!("{} (+{} attack)", self.name(), self.attack_bonus()),
+            ItemKind::Armour => format!("{} (+{} defense)", self.name(), self.defense_bonus()),
+            ItemKind::Key => format!("{} (opens a locked door)", self.label()),
+        }
+    }
+
+    /// Attack it adds when equipped, 0 for anything but weapons.
+    pub fn attack_bonus(&self) -> u16 {
+        match self {
+            Item::Dagger => 2,
+            Item::Sword => 4,
+            Item::Axe => 6,
+            _ => 0,
+        }
+    }
+
+    /// Defense it adds when equipped, 0 for anything but armour.
+    pub fn defense_bonus(&self) -> u16 {
+        match self {
+            Item::LeatherArmour => 1,
+            Item::ChainMail => 2,
+            Item::PlateArmour => 4,
+            _ => 0,
+        }
+    }
+
+    /// Keys can't be sold, their doors would stay locked for good.
+    pub fn sellable(&self) -> bool {
+        self.kind() != ItemKind::Key
+    }
+
+    /// Gold a merchant next to the origin room asks for the item.
+    pub fn base_price(&self) -> u32 {
+        match self {
+            Item::Potion => 10,
+            Item::Dagger | Item::LeatherArmour => 15,
+            Item::Key(_) => 20,
+            Item::Sword | Item::ChainMail => 40,
+            Item::Axe | Item::PlateArmour => 80,
+        }
+    }
+
+    /// Relative chance to find the item in a room `distance` rooms away from the origin. The
+    /// better gear only shows up further out.
+    pub fn spawn_weight(&self, distance: u16) -> f32 {
+        match self {
+            Item::Potion => 4.0,
+            Item::Dagger | Item::LeatherArmour => 2.0,
+            Item::Sword | Item::ChainMail if distance >= 2 => 1.5,
+            Item::Axe | Item::PlateArmour if distance >= 4 => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Items that can be found at `distance`, paired with their weights.
+    pub fn spawn_table(distance: u16) -> Vec<(Item, f32)> {
+        Item::ALL
+            .iter()
+            .map(|item| (*item, item.spawn_weight(distance)))
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect()
+    }
+}
+
+/// Anything that can lie on the floor and be picked up.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Loot {
+    /// A pile of gold coins.
+    Gold(u32),
+    /// A single item.
+    Item(Item),
+}
+
+impl Loot {
+    /// Name for notices, like "12 gold" or "a sword".
+    pub fn name(&self) -> String {
+        match self {
+            Loot::Gold(amount) => format!("{amount} gold"),
+            Loot::Item(item) => format!("a {}", item.label()),
+        }
+    }
+}
+
+/// Loot lying on the floor of a room.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Pickup {
+    /// What lies there.
+    pub loot: Loot,
+    /// Tile it lies on.
+    pub position: Position,
+}
+
+/// Gold, the bag and the equipped gear of the player.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Inventory {
+    /// Coins to spend at merchants.
+    pub gold: u32,
+    /// Carried items, at most ten.
+    pub bag: Vec<Item>,
+    /// Equipped weapon.
+    pub weapon: Option<Item>,
+    /// Equipped armour.
+    pub armour: Option<Item>,
+}
+
+impl Default for Inventory {
+    fn default() -> Inventory {
+        Inventory {
+            gold: 0,
+            bag: vec![Item::Potion, Item::Potion],
+            weapon: None,
+            armour: None,
+        }
+    }
+}
+
+impl Inventory {
+    /// Stores `loot`, gold always fits but items need room in the bag.
+    pub fn add_loot(&mut self, loot: Loot) -> bool {
+        match loot {
+            Loot::Gold(amount) => {
+                self.gold = self.gold.saturating_add(amount);
+                true
+            }
+            Loot::Item(item) => self.add(item),
+        }
+    }
+
+    /// Puts `item` in the bag, unless it is full.
+    pub fn add(&mut self, item: Item) -> bool {
+        if self.bag.len() >= BAG_SIZE {
+            return false;
+        }
+        self.bag.push(item);
+        true
+    }
+
+    /// How many of `item` are in the bag.
+    pub fn count(&self, item: Item) -> usize {
+        self.bag.iter().filter(|x| **x == item).count()
+    }
+
+    /// Takes one `item` out of the bag if there is one.
+    pub fn take(&mut self, item: Item) -> bool {
+        match self.bag.iter().position(|x| *x == item) {
+            Some(index) => {
+                self.bag.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Equips the weapon or armour at `index` in the bag, putting the one worn before back in
+    /// its place. Returns false for anything else.
+    pub fn equip(&mut self, index: usize) -> bool {
+        let Some(item) = self.bag.get(index).copied() else {
+            return false;
+        };
+        let slot = match item.kind() {
+            ItemKind::Weapon => &mut self.weapon,
+            ItemKind::Armour => &mut self.armour,
+            ItemKind::Potion | ItemKind::Key => return false,
+        };
+        match slot.replace(item) {
+            Some(previous) => self.bag[index] = previous,
+            None => {
+                self.bag.remove(index);
+            }
+        }
+        true
+    }
+
+    /// Attack added by the equipped weapon.
+    pub fn attack_bonus(&self) -> u16 {
+        self.weapon.map_or(0, |item| item.attack_bonus())
+    }
+
+    /// Defense added by the equipped armour.
+    pub fn defense_bonus(&self) -> u16 {
+        self.armour.map_or(0, |item| item.defense_bonus())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equipping_swaps_with_the_worn_item() {
+        let mut inventory = Inventory {
+            bag: vec![Item::Potion, Item::Dagger, Item::Sword],
+            ..Inventory::default()
+        };
+        assert!(inventory.equip(1));
+        assert_eq!(inventory.bag, vec![Item::Potion, Item::Sword]);
+        assert!(inventory.equip(1));
+        assert_eq!(inventory.weapon, Some(Item::Sword));
+        assert_eq!(inventory.bag, vec![Item::Potion, Item::Dagger]);
+        assert_eq!(inventory.attack_bonus(), 4);
+        assert!(!inventory.equip(0));
+    }
+
+    #[test]
+    fn full_bag_refuses_items() {
+        let mut inventory = Inventory::default();
+        while inventory.add(Item::Dagger) {}
+        assert_eq!(inventory.bag.len(), BAG_SIZE);
+        assert!(inventory.take(Item::Potion));
+        assert_eq!(inventory.count(Item::Potion), 1);
+        assert!(inventory.add(Item::Dagger));
+    }
+}
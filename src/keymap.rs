@@ -0,0 +1,257 @@
+//! Named actions and the keys bound to them, loaded from `keymap.toml`.
+
+use super::game::GameAction;
+use super::input::parse_key;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use termion::event::Key;
+
+/// Keymap read at startup when no other file is given.
+pub const DEFAULT_KEYMAP_FILE: &str = "keymap.toml";
+
+/// Something a key can be bound to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Walk or select up.
+    MoveUp,
+    /// Walk right or switch the shop mode.
+    MoveRight,
+    /// Walk or select down.
+    MoveDown,
+    /// Walk left or switch the shop mode.
+    MoveLeft,
+    /// Answer yes, use or buy.
+    Confirm,
+    /// Answer no or close the open screen.
+    Decline,
+    /// Leave the game.
+    Quit,
+    /// Show the full map.
+    OpenMap,
+    /// Save the run.
+    Save,
+    /// Try to escape a fight.
+    Flee,
+    /// Hit in combat.
+    Attack,
+    /// Raise the guard in combat.
+    Defend,
+    /// Drink a potion in combat.
+    UseItem,
+    /// Open or close the inventory.
+    Inventory,
+}
+
+impl Action {
+    const ALL: [Action; 14] = [
+        Action::MoveUp,
+        Action::MoveRight,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::Confirm,
+        Action::Decline,
+        Action::Quit,
+        Action::OpenMap,
+        Action::Save,
+        Action::Flee,
+        Action::Attack,
+        Action::Defend,
+        Action::UseItem,
+        Action::Inventory,
+    ];
+
+    /// The name used in keymap and replay files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveUp => "move_up",
+            Action::MoveRight => "move_right",
+            Action::MoveDown => "move_down",
+            Action::MoveLeft => "move_left",
+            Action::Confirm => "confirm",
+            Action::Decline => "decline",
+            Action::Quit => "quit",
+            Action::OpenMap => "open_map",
+            Action::Save => "save",
+            Action::Flee => "flee",
+            Action::Attack => "attack",
+            Action::Defend => "defend",
+            Action::UseItem => "use_item",
+            Action::Inventory => "inventory",
+        }
+    }
+
+    /// The action called `name` in keymap and replay files.
+    pub fn parse(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// The action the game itself handles, `None` for the ones only the app knows about.
+    pub fn game_action(self) -> Option<GameAction> {
+        match self {
+            Action::MoveUp => Some(GameAction::MoveUp),
+            Action::MoveRight => Some(GameAction::MoveRight),
+            Action::MoveDown => Some(GameAction::MoveDown),
+            Action::MoveLeft => Some(GameAction::MoveLeft),
+            Action::Confirm => Some(GameAction::Confirm),
+            Action::Decline => Some(GameAction::Decline),
+            Action::Flee => Some(GameAction::Flee),
+            Action::Attack => Some(GameAction::Attack),
+            Action::Defend => Some(GameAction::Defend),
+            Action::UseItem => Some(GameAction::UseItem),
+            Action::Inventory => Some(GameAction::Inventory),
+            Action::Quit | Action::OpenMap | Action::Save => None,
+        }
+    }
+}
+
+/// Base layouts a keymap file can start from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    /// Arrow keys only, `s` saves.
+    Arrows,
+    /// `wasd` to move, `Ctrl-s` saves.
+    Wasd,
+    /// `hjkl` to move, `s` saves.
+    Vi,
+}
+
+/// Keymap file layout, e.g.
+///
+/// ```toml
+/// preset = "vi"
+///
+/// [bindings]
+/// x = "quit"
+/// Ctrl-s = "save"
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeyMapFile {
+    preset: Option<Preset>,
+    #[serde(default)]
+    bindings: HashMap<String, Action>,
+}
+
+/// Which action each key triggers.
+#[derive(Clone)]
+pub struct KeyMap {
+    bindings: HashMap<Key, Action>,
+}
+
+impl KeyMap {
+    /// The bindings of `preset` with nothing added.
+    pub fn preset(preset: Preset) -> KeyMap {
+        let mut keymap = KeyMap {
+            bindings: HashMap::new(),
+        };
+        // Shared by every preset
+        keymap.bind(Key::Up, Action::MoveUp);
+        keymap.bind(Key::Right, Action::MoveRight);
+        keymap.bind(Key::Down, Action::MoveDown);
+        keymap.bind(Key::Left, Action::MoveLeft);
+        keymap.bind(Key::Char('\n'), Action::Confirm);
+        keymap.bind(Key::Char('y'), Action::Confirm);
+        keymap.bind(Key::Char('Y'), Action::Confirm);
+        keymap.bind(Key::Char('n'), Action::Decline);
+        keymap.bind(Key::Char('N'), Action::Decline);
+        keymap.bind(Key::Esc, Action::Quit);
+        keymap.bind(Key::Char('q'), Action::Quit);
+        keymap.bind(Key::Char('m'), Action::OpenMap);
+        keymap.bind(Key::Char('f'), Action::Flee);
+        keymap.bind(Key::Char('F'), Action::Flee);
+        keymap.bind(Key::Char('1'), Action::Attack);
+        keymap.bind(Key::Char('2'), Action::Defend);
+        keymap.bind(Key::Char('3'), Action::UseItem);
+        keymap.bind(Key::Char('u'), Action::UseItem);
+        keymap.bind(Key::Char('4'), Action::Flee);
+        keymap.bind(Key::Char('i'), Action::Inventory);
+
+        match preset {
+            Preset::Arrows => {
+                keymap.bind(Key::Char('s'), Action::Save);
+            }
+            Preset::Wasd => {
+                keymap.bind(Key::Char('w'), Action::MoveUp);
+                keymap.bind(Key::Char('d'), Action::MoveRight);
+                keymap.bind(Key::Char('s'), Action::MoveDown);
+                keymap.bind(Key::Char('a'), Action::MoveLeft);
+                keymap.bind(Key::Ctrl('s'), Action::Save);
+            }
+            Preset::Vi => {
+                keymap.bind(Key::Char('k'), Action::MoveUp);
+                keymap.bind(Key::Char('l'), Action::MoveRight);
+                keymap.bind(Key::Char('j'), Action::MoveDown);
+                keymap.bind(Key::Char('h'), Action::MoveLeft);
+                keymap.bind(Key::Char('s'), Action::Save);
+            }
+        }
+        keymap
+    }
+
+    /// Starts from the file's preset (arrows if missing) and applies its extra bindings on top.
+    pub fn parse(content: &str) -> Result<KeyMap, Error> {
+        let file: KeyMapFile =
+            toml::from_str(content).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        let mut keymap = KeyMap::preset(file.preset.unwrap_or(Preset::Arrows));
+        for (name, action) in file.bindings {
+            let key = parse_key(&name)
+                .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Unknown key {name}")))?;
+            keymap.bind(key, action);
+        }
+        Ok(keymap)
+    }
+
+    /// Reads a keymap file, see [`KeyMap::parse`].
+    pub fn load(path: &Path) -> Result<KeyMap, Error> {
+        KeyMap::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Binds `key` to `action`, replacing what it was bound to before.
+    pub fn bind(&mut self, key: Key, action: Action) {
+        self.bindings.insert(key, action);
+    }
+
+    /// The action bound to `key`, if any.
+    pub fn action(&self, key: Key) -> Option<Action> {
+        self.bindings.get(&key).copied()
+    }
+}
+
+impl Default for KeyMap {
+    fn default() -> KeyMap {
+        KeyMap::preset(Preset::Arrows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_bindings_override_the_preset() {
+        let keymap =
+            KeyMap::parse("preset = \"vi\"\n[bindings]\nx = \"quit\"\nk = \"save\"\n").unwrap();
+        assert_eq!(keymap.action(Key::Char('h')), Some(Action::MoveLeft));
+        assert_eq!(keymap.action(Key::Char('x')), Some(Action::Quit));
+        assert_eq!(keymap.action(Key::Char('k')), Some(Action::Save));
+        assert_eq!(keymap.action(Key::Up), Some(Action::MoveUp));
+    }
+
+    #[test]
+    fn wasd_moves_save_off_s() {
+        let keymap = KeyMap::preset(Preset::Wasd);
+        assert_eq!(keymap.action(Key::Char('s')), Some(Action::MoveDown));
+        assert_eq!(keymap.action(Key::Ctrl('s')), Some(Action::Save));
+    }
+
+    #[test]
+    fn unknown_actions_and_keys_are_rejected() {
+        assert!(KeyMap::parse("[bindings]\nx = \"dance\"\n").is_err());
+        assert!(KeyMap::parse("[bindings]\nxyz = \"quit\"\n").is_err());
+    }
+}
@@ -0,0 +1,77 @@
+//! The game core of adventure, a small terminal dungeon crawler.
+//!
+//! A [`Game`] holds a whole run. [`Game::apply`] plays a [`GameAction`] and returns the
+//! [`GameEvent`]s it caused without drawing anything, so runs can be simulated headless. The
+//! [`Renderer`] draws a game and its events through the [`Surface`] trait, which works just as
+//! well against the in-memory [`GridSurface`] as in a terminal:
+//!
+//! ```
+//! use adventure::{Balance, Game, GameAction, GridSurface, Renderer};
+//!
+//! let mut game = Game::start(42, Balance::default())?;
+//! let mut renderer = Renderer::new();
+//! let mut screen = GridSurface::new();
+//! renderer.draw_screen(&game, &mut screen)?;
+//! let events = game.apply(GameAction::MoveDown);
+//! renderer.render(&game, &events, &mut screen)?;
+//! println!("{screen}");
+//! # Ok::<(), adventure::AdventureError>(())
+//! ```
+//!
+//! Bots and map analysers can also use the pieces on their own: [`Room::create_next_room`]
+//! generates a room the way the game does, and [`Combat`] plays rounds out against a copy of
+//! the [`Player`].
+//!
+//! The terminal frontend, with key input, key maps, recordings and the command line, is behind
+//! the default `terminal` feature. Turn default features off to depend on the core without
+//! termion.
+
+#![warn(missing_docs)]
+
+mod ai;
+#[cfg(feature = "terminal")]
+pub mod app;
+#[cfg(feature = "terminal")]
+pub mod cli;
+mod combat;
+pub mod config;
+mod consts;
+mod controller;
+mod difficulty;
+mod enemy;
+pub mod error;
+mod event;
+pub mod game;
+#[cfg(all(test, feature = "terminal"))]
+mod harness;
+mod helper;
+#[cfg(feature = "terminal")]
+pub mod input;
+mod inventory;
+#[cfg(feature = "terminal")]
+pub mod keymap;
+mod loot;
+mod player;
+#[cfg(feature = "terminal")]
+pub mod replay;
+mod room;
+pub mod save;
+mod shop;
+pub mod ui;
+
+pub use combat::{Combat, CombatAction, CombatOutcome};
+pub use config::Balance;
+pub use consts::{Door, DoorState, Position};
+pub use difficulty::{
+    Curve, Difficulty, DifficultyCurve, Exponential, Linear, Logistic, Quadratic, Stepped,
+};
+pub use enemy::{Behaviour, Enemy, EnemyKind, EnemyTrait};
+pub use error::{AdventureError, Result};
+pub use event::{GameEvent, TradeRefusal};
+pub use game::{FightPrompt, Game, GameAction, LevelUp, RunStats};
+pub use inventory::{Inventory, Item, ItemKind, Loot, Pickup};
+pub use player::Player;
+pub use room::{HealingKind, HealingSpot, Room, RoomPosition};
+pub use shop::{Merchant, Shop, ShopMode};
+pub use ui::renderer::Renderer;
+pub use ui::surface::{Color, GridSurface, Surface};
@@ -1,9 +0,0 @@
-mod config;
-mod consts;
-pub mod controller;
-mod enemy;
-pub mod game;
-mod helper;
-mod player;
-mod room;
-pub mod ui;
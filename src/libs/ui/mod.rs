@@ -1,2 +0,0 @@
-pub mod dialog;
-pub mod minimap;
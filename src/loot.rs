@@ -0,0 +1,87 @@
+//! What beaten enemies drop.
+
+use super::enemy::{Enemy, EnemyKind};
+use super::helper::select_random_weighted;
+use super::inventory::{Item, ItemKind, Loot};
+use rand::Rng;
+
+/// What a defeated enemy leaves behind, before the exact loot is rolled.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Drop {
+    Nothing,
+    Gold,
+    Potion,
+    Equipment,
+}
+
+/// Drop weights per kind. Every level above the first makes empty hands less likely.
+fn drop_table(enemy: &Enemy) -> Vec<(Drop, f32)> {
+    let (nothing, gold, potion, equipment) = match enemy.kind {
+        EnemyKind::Slime => (5.0, 3.0, 2.0, 0.5),
+        EnemyKind::Skeleton => (3.0, 3.0, 1.0, 2.0),
+        EnemyKind::Archer => (3.0, 4.0, 1.5, 1.5),
+        EnemyKind::Boss => (0.0, 2.0, 1.0, 3.0),
+    };
+    let nothing = nothing / enemy.level as f32;
+    [
+        (Drop::Nothing, nothing),
+        (Drop::Gold, gold),
+        (Drop::Potion, potion),
+        (Drop::Equipment, equipment),
+    ]
+    .into_iter()
+    .filter(|(_, weight)| *weight > 0.0)
+    .collect()
+}
+
+/// Weapons and armour an enemy can drop, better ones as its level grows.
+fn equipment_table(enemy: &Enemy) -> Vec<(Item, f32)> {
+    Item::spawn_table(enemy.level)
+        .into_iter()
+        .filter(|(item, _)| matches!(item.kind(), ItemKind::Weapon | ItemKind::Armour))
+        .collect()
+}
+
+/// Rolls what `enemy` drops when defeated, if anything.
+pub fn roll_loot(enemy: &Enemy, rng: &mut impl Rng) -> Option<Loot> {
+    match select_random_weighted(&drop_table(enemy), rng) {
+        Drop::Nothing => None,
+        Drop::Gold => {
+            let per_level = if enemy.kind == EnemyKind::Boss { 15 } else { 5 };
+            let amount = rng.gen_range(1..=per_level) * enemy.level as u32;
+            Some(Loot::Gold(amount))
+        }
+        Drop::Potion => Some(Loot::Item(Item::Potion)),
+        Drop::Equipment => Some(Loot::Item(*select_random_weighted(
+            &equipment_table(enemy),
+            rng,
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::Position;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn bosses_always_drop_something() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let boss = Enemy::new(EnemyKind::Boss, 5, Position { x: 5, y: 4 });
+        for _ in 0..50 {
+            assert!(roll_loot(&boss, &mut rng).is_some());
+        }
+    }
+
+    #[test]
+    fn low_level_enemies_drop_basic_equipment() {
+        let slime = Enemy::new(EnemyKind::Slime, 1, Position { x: 5, y: 4 });
+        let items: Vec<Item> = equipment_table(&slime)
+            .into_iter()
+            .map(|(item, _)| item)
+            .collect();
+        assert_eq!(items, vec![Item::Dagger, Item::LeatherArmour]);
+    }
+}
@@ -1,55 +1,138 @@
-use crate::libs::ui::minimap::Minimap;
-use libs::controller::PlayerController;
-use libs::game::Game;
-use std::io::{stdin, stdout, Write};
-use termion;
-use termion::event::Key;
-use termion::input::TermRead;
-use termion::raw::IntoRawMode;
-
-mod libs;
+use adventure::app::{App, Flow};
+use adventure::cli::{Command, Options};
+use adventure::config::{Balance, DEFAULT_BALANCE_FILE};
+use adventure::error::{AdventureError, Result};
+use adventure::game::Game;
+use adventure::input::{InputSource, ScriptInput, TerminalInput};
+use adventure::keymap::{KeyMap, DEFAULT_KEYMAP_FILE};
+use adventure::replay::{Recording, RecordingInput, ReplayInput};
+use adventure::save::load_game;
+use adventure::ui::renderer::Renderer;
+use adventure::ui::surface::{GridSurface, Surface, TerminalSurface};
+use adventure::ui::terminal::TerminalSession;
+use std::io::{stdin, stdout};
+use std::path::PathBuf;
+use std::process;
 
 fn main() {
-    const DEBUG: bool = true;
-    let stdin = stdin();
-    let mut stdout = stdout().into_raw_mode().unwrap();
-
-    write!(
-        stdout,
-        "{}{}{}",
-        termion::clear::All,
-        termion::cursor::Goto(1, 1),
-        termion::cursor::Hide,
-    )
-    .expect("Failed to do pres-start chores");
-    stdout.flush().unwrap();
-
-    let mut game = Game::start();
-    Minimap::print_minimap(&game.rooms, &game, &mut stdout);
+    // The terminal session is over by the time `run` returns, so the error lands on the normal screen
+    if let Err(err) = run(Options::parse()) {
+        eprintln!("{err}");
+        process::exit(1);
+    }
+}
 
-    game.current_room
-        .render_room(game.player.position.clone(), &mut stdout);
+fn run(options: Options) -> Result<()> {
+    if options.command == Command::Help {
+        println!("{}", Options::usage());
+        return Ok(());
+    }
+    let replay = match &options.replay {
+        Some(path) => {
+            Some(Recording::load(path).map_err(AdventureError::file("load replay", path))?)
+        }
+        None => None,
+    };
+    let config = file_or_default(&options.config, DEFAULT_BALANCE_FILE);
+    let mut balance = config
+        .as_deref()
+        .map(|path| Balance::load(path).map_err(AdventureError::file("load balance config", path)))
+        .transpose()?
+        .unwrap_or_default();
+    if let Some(difficulty) = options.difficulty {
+        balance.difficulty = difficulty;
+    }
+    if let (Some(recording), Some(path)) = (&replay, &options.replay) {
+        recording
+            .check(&balance)
+            .map_err(AdventureError::file("replay", path))?;
+    }
+    let game = match (&options.load, &replay) {
+        (Some(path), _) => load_game(path, balance)?,
+        (None, Some(recording)) => Game::start(recording.seed, balance)?,
+        (None, None) => Game::start(options.seed.unwrap_or_else(rand::random), balance)?,
+    };
+    let keymap_file = file_or_default(&options.keymap, DEFAULT_KEYMAP_FILE);
+    let keymap = keymap_file
+        .as_deref()
+        .map(|path| KeyMap::load(path).map_err(AdventureError::file("load keymap", path)))
+        .transpose()?
+        .unwrap_or_default();
+    let mut app = App::new(game, keymap, options.debug);
 
-    for c in stdin.keys() {
-        match c.unwrap() {
-            Key::Esc | Key::Char('q') => break,
-            Key::Up => PlayerController::move_up(&mut game, &mut stdout),
-            Key::Right => PlayerController::move_right(&mut game, &mut stdout),
-            Key::Down => PlayerController::move_down(&mut game, &mut stdout),
-            Key::Left => PlayerController::move_left(&mut game, &mut stdout),
-            Key::Char('y') | Key::Char('Y') | Key::Char('\n') => {
-                if !game.player.fighting {
-                    continue;
-                }
-                game.fight(&mut stdout);
+    match &options.command {
+        Command::Play => {
+            let terminal_input = TerminalInput::new(stdin());
+            let mut input: Box<dyn InputSource> = match &options.record {
+                Some(path) => Box::new(
+                    RecordingInput::create(
+                        path,
+                        app.game.seed(),
+                        app.game.balance(),
+                        app.keymap.clone(),
+                        terminal_input,
+                    )
+                    .map_err(AdventureError::file("start recording", path))?,
+                ),
+                None => Box::new(terminal_input),
+            };
+            let _session = TerminalSession::start().map_err(AdventureError::Terminal)?;
+            let mut surface = TerminalSurface::new(stdout(), options.color);
+            play(&mut app, replay, &options, input.as_mut(), &mut surface)
+        }
+        Command::Script(path) => {
+            let mut input = ScriptInput::from_file(path)
+                .map_err(AdventureError::file("load key script", path))?;
+            let mut surface = GridSurface::new();
+            app.draw_screen(&mut surface)?;
+            app.play(&mut input, &mut surface)?;
+            println!("{surface}");
+            Ok(())
+        }
+        Command::Check => {
+            // Everything was loaded above, reaching this point means it all made sense
+            let files = [config, keymap_file, options.load.clone()];
+            for path in files.iter().flatten() {
+                println!("{} is fine", path.display());
+            }
+            if files.iter().all(Option::is_none) {
+                println!("Nothing to check, the built-in balance and keymap are in use");
             }
-            _ => continue,
+            Ok(())
         }
-        write!(stdout, "{}", termion::cursor::Goto(1, 1)).expect("Failed move to 1 1");
-        if DEBUG {
-            Minimap::print_minimap(&game.rooms, &game, &mut stdout);
+        // Printed before anything was loaded
+        Command::Help => Ok(()),
+    }
+}
+
+/// The file given on the command line, else `default` if it exists in the working directory.
+fn file_or_default(given: &Option<PathBuf>, default: &str) -> Option<PathBuf> {
+    match given {
+        Some(path) => Some(path.clone()),
+        None => Some(PathBuf::from(default)).filter(|path| path.exists()),
+    }
+}
+
+/// Plays the replay, if any, and then the player's own keys.
+fn play(
+    app: &mut App,
+    replay: Option<Recording>,
+    options: &Options,
+    input: &mut dyn InputSource,
+    surface: &mut dyn Surface,
+) -> Result<()> {
+    app.draw_screen(surface)?;
+    let mut flow = Flow::Continue;
+    if let Some(recording) = replay {
+        let mut replay_input = ReplayInput::new(recording, options.speed);
+        flow = app.replay(&mut replay_input, surface)?;
+    }
+    if flow == Flow::Continue {
+        if options.replay.is_some() {
+            // Hand control to the player once the recording runs out
+            Renderer::draw_notice(&app.game, "Replay finished", surface)?;
         }
-        stdout.flush().unwrap();
+        app.play(input, surface)?;
     }
-    write!(stdout, "{}", termion::cursor::Goto(1, 1)).expect("");
+    Ok(())
 }
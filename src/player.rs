@@ -0,0 +1,155 @@
+//! The player's stats, equipment and levelling.
+
+use super::config::Balance;
+use super::consts::Position;
+use super::inventory::{Inventory, Item, POTION_HEALING};
+use serde::{Deserialize, Serialize};
+
+const BASE_HEALTH: u16 = 100;
+/// Max health gained, and healed, on every level-up.
+pub const LEVEL_UP_HEALTH: u16 = 10;
+
+/// The adventurer, with their level, health and inventory.
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct Player {
+    /// Tile the player stands on in the current room.
+    pub position: Position,
+    /// Starts at 1, raises attack, defense and max health.
+    pub level: u16,
+    /// Gold, bag and equipped gear.
+    pub inventory: Inventory,
+    /// XP collected towards the next level.
+    pub xp: u32,
+    /// Moves made so far, drives health regeneration.
+    pub moves: u32,
+    health: u16,
+}
+
+impl Player {
+    /// A level 1 player with full health and two potions at `position`.
+    pub fn new(position: Position) -> Player {
+        Player {
+            position,
+            level: 1,
+            inventory: Inventory::default(),
+            xp: 0,
+            moves: 0,
+            health: BASE_HEALTH,
+        }
+    }
+
+    /// Takes `value` off the health, down to 0.
+    pub(crate) fn decrease_health(&mut self, value: u16) {
+        self.health = self.health.saturating_sub(value);
+    }
+
+    /// Adds `value` to the health, up to the max. Returns the health actually gained.
+    pub(crate) fn heal(&mut self, value: u16) -> u16 {
+        let before = self.health;
+        self.health = self.health.saturating_add(value).min(self.max_health());
+        self.health - before
+    }
+
+    /// Drinks a potion if there is one left, returning the health it gave back.
+    pub(crate) fn drink_potion(&mut self) -> Option<u16> {
+        if !self.inventory.take(Item::Potion) {
+            return None;
+        }
+        Some(self.heal(POTION_HEALING))
+    }
+
+    /// Adds `xp` and levels up as often as it allows, returning the number of levels gained.
+    pub(crate) fn gain_xp(&mut self, xp: u32, balance: &Balance) -> u16 {
+        self.xp = self.xp.saturating_add(xp);
+        let mut levels = 0;
+        while self.xp >= balance.xp_to_next_level(self.level) {
+            self.xp -= balance.xp_to_next_level(self.level);
+            self.level += 1;
+            self.heal(LEVEL_UP_HEALTH);
+            levels += 1;
+        }
+        levels
+    }
+
+    /// Health left.
+    pub fn health(&self) -> u16 {
+        self.health
+    }
+
+    /// Health is capped by level, each level-up raises the cap by `LEVEL_UP_HEALTH`.
+    pub fn max_health(&self) -> u16 {
+        BASE_HEALTH.saturating_add(LEVEL_UP_HEALTH.saturating_mul(self.level.saturating_sub(1)))
+    }
+
+    /// Restores the health to the max.
+    pub(crate) fn heal_fully(&mut self) {
+        self.health = self.max_health();
+    }
+
+    /// Counts a move and heals on every `balance.regen_interval`-th one.
+    pub(crate) fn regenerate(&mut self, balance: &Balance) {
+        self.moves += 1;
+        if self.moves.is_multiple_of(balance.regen_interval as u32) {
+            self.heal(balance.regen_amount);
+        }
+    }
+
+    /// Damage dealt before the enemy's defense.
+    pub fn attack(&self) -> u16 {
+        5 + 2 * self.level + self.inventory.attack_bonus()
+    }
+
+    /// Damage shrugged off from each hit.
+    pub fn defense(&self) -> u16 {
+        1 + self.level + self.inventory.defense_bonus()
+    }
+
+    /// Whether the health ran out.
+    pub fn is_dead(&self) -> bool {
+        self.health == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xp_carries_over_several_level_ups() {
+        let balance = Balance::default();
+        let mut player = Player::new(Position { x: 5, y: 3 });
+        player.decrease_health(50);
+        let xp = balance.xp_to_next_level(1) + balance.xp_to_next_level(2) + 3;
+        assert_eq!(player.gain_xp(xp, &balance), 2);
+        assert_eq!(player.level, 3);
+        assert_eq!(player.xp, 3);
+        assert_eq!(player.max_health(), 100 + 2 * LEVEL_UP_HEALTH);
+        assert_eq!(player.health(), 50 + 2 * LEVEL_UP_HEALTH);
+    }
+
+    #[test]
+    fn regenerates_every_interval_up_to_the_cap() {
+        let balance = Balance::default();
+        let mut player = Player::new(Position { x: 5, y: 3 });
+        player.decrease_health(3);
+        for _ in 0..balance.regen_interval - 1 {
+            player.regenerate(&balance);
+        }
+        assert_eq!(player.health(), 97);
+        player.regenerate(&balance);
+        assert_eq!(player.health(), 99);
+        for _ in 0..balance.regen_interval {
+            player.regenerate(&balance);
+        }
+        assert_eq!(player.health(), player.max_health());
+    }
+
+    #[test]
+    fn potions_report_the_health_they_gave_back() {
+        let mut player = Player::new(Position { x: 5, y: 3 });
+        assert_eq!(player.drink_potion(), Some(0));
+        player.decrease_health(12);
+        assert_eq!(player.drink_potion(), Some(12));
+        assert_eq!(player.drink_potion(), None);
+    }
+}
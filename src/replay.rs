@@ -0,0 +1,253 @@
+//! Recording the actions of a run and playing them back.
+
+use super::config::Balance;
+use super::difficulty::Difficulty;
+use super::error::{self, AdventureError};
+use super::input::InputSource;
+use super::keymap::{Action, KeyMap};
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{BufWriter, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+use termion::event::Key;
+
+const REPLAY_HEADER: &str = "# adventure replay v2";
+/// Slowest replay speed the command line accepts, a hundred times slower than recorded.
+pub const MIN_SPEED: f32 = 0.01;
+
+/// Seed, settings and timed actions of a recorded session.
+///
+/// Actions rather than keys are stored, so a replay plays the same under any keymap. On disk it
+/// is a header line, `seed <n>`, `difficulty <name>`, `balance <fingerprint>` and then one
+/// `<milliseconds> <action name>` line per action.
+pub struct Recording {
+    /// Seed the session started from.
+    pub seed: u64,
+    /// Difficulty of the balance it was played with.
+    pub difficulty: Difficulty,
+    /// `Balance::fingerprint` of the balance the session was played with.
+    pub balance: u64,
+    /// Milliseconds since the start and the action played then.
+    pub actions: Vec<(u64, Action)>,
+}
+
+impl Recording {
+    /// Reads a replay file, see [`Recording::parse`].
+    pub fn load(path: &Path) -> Result<Recording, Error> {
+        Recording::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses a replay, rejecting other files and unknown actions.
+    pub fn parse(content: &str) -> Result<Recording, Error> {
+        let invalid = |message: String| Error::new(ErrorKind::InvalidData, message);
+        let mut lines = content.lines();
+        if lines.next() != Some(REPLAY_HEADER) {
+            return Err(invalid(String::from("Not an adventure replay file")));
+        }
+        let mut field = |name: &str| {
+            lines
+                .next()
+                .and_then(|line| line.strip_prefix(name))
+                .and_then(|line| line.strip_prefix(' '))
+                .ok_or_else(|| invalid(format!("Replay is missing its {name}")))
+        };
+        let seed = field("seed")?
+            .parse::<u64>()
+            .map_err(|_| invalid(String::from("Malformed seed")))?;
+        let difficulty = field("difficulty")?;
+        let difficulty = Difficulty::parse(difficulty)
+            .ok_or_else(|| invalid(format!("Unknown difficulty {difficulty}")))?;
+        let balance = u64::from_str_radix(field("balance")?, 16)
+            .map_err(|_| invalid(String::from("Malformed balance fingerprint")))?;
+
+        let mut actions = Vec::new();
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            let (time, name) = line
+                .split_once(' ')
+                .ok_or_else(|| invalid(format!("Malformed replay line {line}")))?;
+            let time = time
+                .parse::<u64>()
+                .map_err(|_| invalid(format!("Malformed timestamp in {line}")))?;
+            let action =
+                Action::parse(name).ok_or_else(|| invalid(format!("Unknown action {name}")))?;
+            actions.push((time, action));
+        }
+        Ok(Recording {
+            seed,
+            difficulty,
+            balance,
+            actions,
+        })
+    }
+
+    /// Refuses to play back on other settings than the session was recorded with.
+    pub fn check(&self, balance: &Balance) -> Result<(), Error> {
+        balance.check_matches(self.difficulty, self.balance, "recorded")
+    }
+}
+
+/// Passes keys through from another source while writing the actions they map to to a replay
+/// file.
+pub struct RecordingInput<I: InputSource> {
+    inner: I,
+    keymap: KeyMap,
+    out: BufWriter<File>,
+    path: PathBuf,
+    start: Instant,
+}
+
+impl<I: InputSource> RecordingInput<I> {
+    /// Writes the header for a session from `seed` with `balance`, then records every key of
+    /// `inner` that `keymap` binds as its action.
+    pub fn create(
+        path: &Path,
+        seed: u64,
+        balance: &Balance,
+        keymap: KeyMap,
+        inner: I,
+    ) -> Result<RecordingInput<I>, Error> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "{REPLAY_HEADER}")?;
+        writeln!(out, "seed {seed}")?;
+        writeln!(out, "difficulty {}", balance.difficulty.name())?;
+        writeln!(out, "balance {:016x}", balance.fingerprint())?;
+        out.flush()?;
+        Ok(RecordingInput {
+            inner,
+            keymap,
+            out,
+            path: path.to_path_buf(),
+            start: Instant::now(),
+        })
+    }
+}
+
+impl<I: InputSource> InputSource for RecordingInput<I> {
+    fn next_key(&mut self) -> error::Result<Option<Key>> {
+        let Some(key) = self.inner.next_key()? else {
+            return Ok(None);
+        };
+        if let Some(action) = self.keymap.action(key) {
+            let time = self.start.elapsed().as_millis();
+            // Flush every action, the session may well end in a crash we want to see
+            writeln!(self.out, "{time} {}", action.name())
+                .and_then(|_| self.out.flush())
+                .map_err(AdventureError::file("write", &self.path))?;
+        }
+        Ok(Some(key))
+    }
+}
+
+/// Plays recorded actions back with their original timing scaled by `speed`.
+pub struct ReplayInput {
+    actions: VecDeque<(u64, Action)>,
+    speed: f32,
+    start: Instant,
+}
+
+impl ReplayInput {
+    /// Plays `recording` back, `speed` 2 is twice as fast.
+    pub fn new(recording: Recording, speed: f32) -> ReplayInput {
+        ReplayInput {
+            actions: recording.actions.into(),
+            speed,
+            start: Instant::now(),
+        }
+    }
+
+    /// Waits until the next action is due and returns it, `None` once the recording ran out.
+    pub fn next_action(&mut self) -> Option<Action> {
+        let (time, action) = self.actions.pop_front()?;
+        // Absurdly slow speeds wait for good rather than overflow
+        let due =
+            Duration::try_from_secs_f32(time as f32 / 1000.0 / self.speed).unwrap_or(Duration::MAX);
+        if let Some(wait) = due.checked_sub(self.start.elapsed()) {
+            thread::sleep(wait);
+        }
+        Some(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::App;
+    use crate::game::Game;
+    use crate::input::ScriptInput;
+    use crate::keymap::Preset;
+    use crate::ui::surface::GridSurface;
+
+    #[test]
+    fn recorded_actions_load_back() {
+        let path = std::env::temp_dir().join(format!("adventure-{}.replay", std::process::id()));
+        let balance = Balance::default();
+        // Unbound keys do nothing and are left out
+        let script = ScriptInput::new(vec![
+            Key::Char('w'),
+            Key::Char('y'),
+            Key::Char('z'),
+            Key::Left,
+        ]);
+        let keymap = KeyMap::preset(Preset::Wasd);
+        let mut input = RecordingInput::create(&path, 7, &balance, keymap, script).unwrap();
+        while input.next_key().unwrap().is_some() {}
+
+        let recording = Recording::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(recording.seed, 7);
+        assert!(recording.check(&balance).is_ok());
+        let actions: Vec<Action> = recording
+            .actions
+            .iter()
+            .map(|(_, action)| *action)
+            .collect();
+        assert_eq!(
+            actions,
+            vec![Action::MoveUp, Action::Confirm, Action::MoveLeft]
+        );
+    }
+
+    #[test]
+    fn replayed_saves_leave_the_save_file_alone() {
+        let balance = Balance::default();
+        let recording = Recording {
+            seed: 1,
+            difficulty: balance.difficulty,
+            balance: balance.fingerprint(),
+            actions: vec![(0, Action::MoveDown), (0, Action::Save)],
+        };
+        let mut app = App::new(Game::start(1, balance).unwrap(), KeyMap::default(), false);
+        let mut surface = GridSurface::new();
+        app.draw_screen(&mut surface).unwrap();
+        app.replay(&mut ReplayInput::new(recording, 1.0), &mut surface)
+            .unwrap();
+        assert!(surface.line(1).ends_with("Not saved during a replay"));
+    }
+
+    #[test]
+    fn replay_without_header_is_rejected() {
+        assert!(Recording::parse("seed 1\n0 move_up").is_err());
+    }
+
+    #[test]
+    fn replay_on_other_settings_is_refused() {
+        let hard = Balance {
+            difficulty: Difficulty::Hard,
+            ..Balance::default()
+        };
+        let recording = Recording::parse(&format!(
+            "{REPLAY_HEADER}\nseed 1\ndifficulty hard\nbalance {:016x}\n0 move_up",
+            hard.fingerprint()
+        ))
+        .unwrap();
+        assert!(recording.check(&hard).is_ok());
+        assert!(recording.check(&Balance::default()).is_err());
+        let tuned = Balance {
+            flee_probability: 0.5,
+            ..hard.clone()
+        };
+        assert!(recording.check(&tuned).is_err());
+    }
+}
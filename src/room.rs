@@ -0,0 +1,450 @@
+//! Rooms: their doors, enemies, pickups and how they are generated.
+
+use super::config::Balance;
+use super::consts::Door::{BOTTOM, LEFT, RIGHT, TOP};
+use super::consts::{Door, DoorState, Position};
+use super::enemy::{Enemy, EnemyKind};
+use super::helper::{generate_enemy_position, select_random_weighted, spawn_positions};
+use super::inventory::{Item, Loot, Pickup};
+use super::shop::Merchant;
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Where a room sits on the dungeon grid, the first room is at 0,0 and y grows upwards.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct RoomPosition {
+    /// Columns to the right of the first room.
+    pub x: i8,
+    /// Rows above the first room.
+    pub y: i8,
+}
+
+/// What kind of healing spot a room has, only the look differs.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealingKind {
+    /// Drawn as a cross.
+    Shrine,
+    /// Drawn as water.
+    Fountain,
+}
+
+/// A tile that fully heals the player the first time they step on it.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct HealingSpot {
+    /// Shrine or fountain.
+    pub kind: HealingKind,
+    /// Tile it sits on.
+    pub position: Position,
+    /// Whether it has healed the player already.
+    pub used: bool,
+}
+
+impl HealingKind {
+    /// Name for notices.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HealingKind::Shrine => "shrine",
+            HealingKind::Fountain => "fountain",
+        }
+    }
+}
+
+/// One room of the dungeon with its doors and everything in it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Room {
+    /// Where the room sits on the dungeon grid.
+    pub grid_position: RoomPosition,
+    /// Sides with a door in the wall.
+    pub doors: Vec<Door>,
+    /// Doors that are not open, any door missing here is.
+    pub door_states: Vec<(Door, DoorState)>,
+    /// Enemies in the room, by position.
+    pub enemies: Vec<Enemy>,
+    /// The healing spot, if the room has one.
+    pub healing: Option<HealingSpot>,
+    /// Loot lying on the floor.
+    pub pickups: Vec<Pickup>,
+    /// The merchant, if one set up shop here.
+    pub merchant: Option<Merchant>,
+}
+
+impl Room {
+    /// An empty room with `doors`, all of them open.
+    pub fn new(grid_position: RoomPosition, doors: Vec<Door>) -> Room {
+        Room {
+            grid_position,
+            doors,
+            door_states: Vec::new(),
+            enemies: Vec::new(),
+            healing: None,
+            pickups: Vec::new(),
+            merchant: None,
+        }
+    }
+
+    /// Moves `enemies` into the room.
+    pub(crate) fn add_enemies(&mut self, enemies: &mut Vec<Enemy>) {
+        self.enemies.append(enemies);
+    }
+
+    /// The first pickup at `position`, if any.
+    pub fn get_pickup_at_position(&self, position: Position) -> Option<Pickup> {
+        self.pickups
+            .iter()
+            .find(|pickup| pickup.position == position)
+            .copied()
+    }
+
+    /// Removes the first pickup at `position`, the one `get_pickup_at_position` returns.
+    pub(crate) fn remove_pickup(&mut self, position: Position) {
+        if let Some(index) = self
+            .pickups
+            .iter()
+            .position(|pickup| pickup.position == position)
+        {
+            self.pickups.remove(index);
+        }
+    }
+
+    /// Whether `door` is open, locked or sealed.
+    pub fn door_state(&self, door: Door) -> DoorState {
+        self.door_states
+            .iter()
+            .find(|(other, _)| *other == door)
+            .map_or(DoorState::Open, |(_, state)| *state)
+    }
+
+    /// Opens `door` for good.
+    pub(crate) fn open_door(&mut self, door: Door) {
+        self.door_states.retain(|(other, _)| *other != door);
+    }
+
+    /// Opens every sealed door, returns whether there were any.
+    pub(crate) fn unseal_doors(&mut self) -> bool {
+        let sealed = self.door_states.len();
+        self.door_states
+            .retain(|(_, state)| *state != DoorState::Sealed);
+        self.door_states.len() < sealed
+    }
+
+    /// Whether the merchant stands at `position`.
+    pub fn has_merchant_at(&self, position: Position) -> bool {
+        self.merchant
+            .as_ref()
+            .is_some_and(|merchant| merchant.position == position)
+    }
+
+    /// Replaces the enemy standing on the same tile with `enemy`.
+    pub(crate) fn update_enemy(&mut self, enemy: Enemy) {
+        if let Some(room_enemy) = self
+            .enemies
+            .iter_mut()
+            .find(|x| x.position == enemy.position)
+        {
+            *room_enemy = enemy;
+        }
+    }
+
+    /// Removes the enemy at `position`, if any.
+    pub(crate) fn remove_enemy(&mut self, position: Position) {
+        self.enemies.retain(|enemy| enemy.position != position);
+    }
+
+    /// The enemy at `position`, if any.
+    pub fn get_enemy_at_position(&self, position: Position) -> Option<Enemy> {
+        for enemy in &self.enemies {
+            if enemy.position == position {
+                return Some(*enemy);
+            }
+        }
+        None
+    }
+
+    /// Generates the room at `grid_position`, entered through its `direction` door. Doors
+    /// leading to `rooms` that already exist are matched up, the rest are rolled from `rng`
+    /// along with everything in the room.
+    ///
+    /// This is how the game generates every room, so a map analyser can generate ahead with a
+    /// seeded RNG. Panics on a `balance` that fails `Balance::validate`.
+    pub fn create_next_room(
+        grid_position: RoomPosition,
+        direction: Door,
+        rooms: &HashMap<RoomPosition, Room>,
+        balance: &Balance,
+        rng: &mut impl Rng,
+    ) -> Room {
+        // Doors - 1 from the coming direction, next random
+        let all_directions = vec![TOP, BOTTOM, LEFT, RIGHT];
+
+        let mut neighbour_rooms = vec![];
+        let mut banned_directions = vec![];
+        // Checking room on the right
+        if let Some(room) = rooms.get(&RoomPosition {
+            x: grid_position.x + 1,
+            y: grid_position.y,
+        }) {
+            if room.doors.contains(&LEFT) {
+                neighbour_rooms.push(RIGHT);
+            } else {
+                banned_directions.push(RIGHT);
+            }
+        }
+        // Checking room on the left
+        if let Some(room) = rooms.get(&RoomPosition {
+            x: grid_position.x - 1,
+            y: grid_position.y,
+        }) {
+            if room.doors.contains(&RIGHT) {
+                neighbour_rooms.push(LEFT);
+            } else {
+                banned_directions.push(LEFT);
+            }
+        }
+        // Checking room on the top
+        if let Some(room) = rooms.get(&RoomPosition {
+            x: grid_position.x,
+            y: grid_position.y + 1,
+        }) {
+            if room.doors.contains(&BOTTOM) {
+                neighbour_rooms.push(TOP);
+            } else {
+                banned_directions.push(TOP);
+            }
+        }
+        // Checking room on the bottom
+        if let Some(room) = rooms.get(&RoomPosition {
+            x: grid_position.x,
+            y: grid_position.y - 1,
+        }) {
+            if room.doors.contains(&TOP) {
+                neighbour_rooms.push(BOTTOM);
+            } else {
+                banned_directions.push(BOTTOM);
+            }
+        }
+
+        neighbour_rooms.push(direction);
+
+        // Keep the candidate order fixed so the same seed always picks the same doors
+        let result: Vec<_> = all_directions
+            .into_iter()
+            .filter(|door| !neighbour_rooms.contains(door) && !banned_directions.contains(door))
+            .collect();
+
+        let mut num_doors: u8 = 0;
+        if result.len() == 1 {
+            num_doors = *select_random_weighted::<u8>(&balance.door_dist_1, rng);
+        } else if result.len() == 2 {
+            num_doors = *select_random_weighted::<u8>(&balance.door_dist_2, rng);
+        } else if result.len() == 3 {
+            num_doors = *select_random_weighted::<u8>(&balance.door_dist_3, rng);
+        }
+
+        // Doors into rooms nobody has generated yet
+        let unexplored: Vec<_> = result
+            .choose_multiple(rng, num_doors as usize)
+            .cloned()
+            .collect();
+        let mut new_doors = unexplored.clone();
+        new_doors.append(&mut neighbour_rooms);
+
+        let mut new_room = Room::new(grid_position, new_doors);
+        let manhattan_distance_from_center: u16 =
+            grid_position.x.unsigned_abs() as u16 + grid_position.y.unsigned_abs() as u16;
+
+        // The key goes into this room, so it is always found before its lock. Room counts only
+        // grow, which keeps key numbers unique.
+        if !unexplored.is_empty() && rng.gen_bool(balance.locked_door_probability as f64) {
+            let door = unexplored[rng.gen_range(0..unexplored.len())];
+            let number = rooms.len() as u16;
+            // The room is still empty, so there is always a tile for the key
+            if let Some(position) = new_room.free_position(rng) {
+                new_room.door_states.push((door, DoorState::Locked(number)));
+                new_room.pickups.push(Pickup {
+                    loot: Loot::Item(Item::Key(number)),
+                    position,
+                });
+            }
+        }
+
+        // Healing rooms are a safe haven, so they get no enemies
+        if rng.gen_bool(balance.healing_room_probability as f64) {
+            let kind = if rng.gen_bool(0.5) {
+                HealingKind::Shrine
+            } else {
+                HealingKind::Fountain
+            };
+            new_room.healing = new_room.free_position(rng).map(|position| HealingSpot {
+                kind,
+                position,
+                used: false,
+            });
+            return new_room;
+        }
+
+        // Merchants keep to themselves as well
+        if rng.gen_bool(balance.merchant_room_probability as f64) {
+            new_room.merchant = new_room
+                .free_position(rng)
+                .map(|position| Merchant::generate(position, manhattan_distance_from_center, rng));
+            return new_room;
+        }
+
+        // Place items first, enemies never start on top of one
+        if rng.gen_bool(balance.item_probability as f64) {
+            let item =
+                *select_random_weighted(&Item::spawn_table(manhattan_distance_from_center), rng);
+            if let Some(position) = new_room.free_position(rng) {
+                new_room.pickups.push(Pickup {
+                    loot: Loot::Item(item),
+                    position,
+                });
+            }
+        }
+
+        if rng.gen_bool(balance.gold_probability as f64) {
+            // Gold piles get bigger further out
+            let amount = rng.gen_range(1..=5) * (manhattan_distance_from_center as u32 + 1);
+            if let Some(position) = new_room.free_position(rng) {
+                new_room.pickups.push(Pickup {
+                    loot: Loot::Gold(amount),
+                    position,
+                });
+            }
+        }
+
+        // Generate enemies
+        let num_enemies = *select_random_weighted::<u8>(&balance.enemy_dist, rng);
+        if num_enemies == 0 {
+            return new_room;
+        }
+        for _ in 0..num_enemies {
+            // A full room takes no more enemies
+            let Some(position) = new_room.free_position(rng) else {
+                break;
+            };
+            let level = balance.enemy_level_function(manhattan_distance_from_center);
+            let kind = *select_random_weighted(
+                &EnemyKind::spawn_table(manhattan_distance_from_center),
+                rng,
+            );
+
+            let enemy = Enemy::new(kind, level, position);
+            new_room.add_enemies(&mut vec![enemy]);
+        }
+
+        // Seal one of the other ways on until the room is cleared
+        let open: Vec<_> = unexplored
+            .into_iter()
+            .filter(|door| new_room.door_state(*door) == DoorState::Open)
+            .collect();
+        if !open.is_empty() && rng.gen_bool(balance.sealed_door_probability as f64) {
+            let door = open[rng.gen_range(0..open.len())];
+            new_room.door_states.push((door, DoorState::Sealed));
+        }
+
+        new_room
+    }
+
+    /// A random tile with nothing placed on it yet, `None` once every spawn tile is taken.
+    fn free_position(&self, rng: &mut impl Rng) -> Option<Position> {
+        let taken: Vec<Position> = self
+            .pickups
+            .iter()
+            .map(|pickup| pickup.position)
+            .chain(self.enemies.iter().map(|enemy| enemy.position))
+            .chain(self.healing.map(|healing| healing.position))
+            .chain(self.merchant.as_ref().map(|merchant| merchant.position))
+            .collect();
+        if spawn_positions().all(|position| taken.contains(&position)) {
+            return None;
+        }
+        let mut position = generate_enemy_position(rng);
+        while taken.contains(&position) {
+            position = generate_enemy_position(rng);
+        }
+        Some(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn locked_doors_have_their_key_in_the_room() {
+        let balance = Balance {
+            locked_door_probability: 1.0,
+            ..Balance::default()
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let mut rooms = HashMap::new();
+        let mut locks = 0;
+        for x in 1..40 {
+            let position = RoomPosition { x, y: 0 };
+            let room = Room::create_next_room(position, LEFT, &rooms, &balance, &mut rng);
+            for (door, state) in &room.door_states {
+                let DoorState::Locked(number) = state else {
+                    continue;
+                };
+                locks += 1;
+                assert_ne!(*door, LEFT, "The way back must stay open");
+                let key = Loot::Item(Item::Key(*number));
+                assert!(room.pickups.iter().any(|pickup| pickup.loot == key));
+            }
+            rooms.insert(position, room);
+        }
+        assert!(locks > 0);
+    }
+
+    #[test]
+    fn crowded_rooms_give_every_enemy_and_pickup_a_tile() {
+        let balance = Balance {
+            enemy_dist: vec![(21, 1.0)],
+            item_probability: 1.0,
+            gold_probability: 1.0,
+            locked_door_probability: 1.0,
+            healing_room_probability: 0.0,
+            merchant_room_probability: 0.0,
+            ..Balance::default()
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let mut room = Room::create_next_room(
+            RoomPosition { x: 0, y: 1 },
+            BOTTOM,
+            &HashMap::new(),
+            &balance,
+            &mut rng,
+        );
+        let mut taken: Vec<Position> = room.enemies.iter().map(|enemy| enemy.position).collect();
+        taken.extend(room.pickups.iter().map(|pickup| pickup.position));
+        assert_eq!(room.enemies.len(), 21);
+        assert!(taken
+            .iter()
+            .enumerate()
+            .all(|(i, tile)| !taken[..i].contains(tile)));
+
+        // Once every tile is taken there is nowhere left to spawn
+        for position in spawn_positions() {
+            if !taken.contains(&position) {
+                room.enemies.push(Enemy::new(EnemyKind::Slime, 1, position));
+            }
+        }
+        assert_eq!(room.free_position(&mut rng), None);
+    }
+
+    #[test]
+    fn clearing_a_room_only_opens_sealed_doors() {
+        let mut room = Room::new(RoomPosition { x: 0, y: 1 }, vec![TOP, LEFT, BOTTOM]);
+        room.door_states = vec![(TOP, DoorState::Sealed), (LEFT, DoorState::Locked(3))];
+        assert!(room.unseal_doors());
+        assert_eq!(room.door_state(TOP), DoorState::Open);
+        assert_eq!(room.door_state(LEFT), DoorState::Locked(3));
+        assert!(!room.unseal_doors());
+    }
+}
@@ -0,0 +1,250 @@
+//! Saving a run to a JSON file and loading it back.
+
+use super::combat::Combat;
+use super::config::Balance;
+use super::difficulty::Difficulty;
+use super::error::{AdventureError, Result};
+use super::game::{FightPrompt, Game, LevelUp, RunStats};
+use super::inventory::BAG_SIZE;
+use super::player::Player;
+use super::room::{Room, RoomPosition};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Error};
+use std::path::Path;
+
+/// Bumped on every change to what a save holds. Saves from other versions are refused rather
+/// than patched up, so the saved types have no serde defaults.
+pub const SAVE_VERSION: u32 = 4;
+/// Where the save action writes the run to.
+pub const DEFAULT_SAVE_FILE: &str = "adventure.sav";
+
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    /// Settings the run was played with, it only resumes on the same ones.
+    difficulty: Difficulty,
+    /// `Balance::fingerprint` of those settings.
+    balance: u64,
+    seed: u64,
+    rng: ChaCha8Rng,
+    player: Player,
+    current_room: RoomPosition,
+    fight_prompt: FightPrompt,
+    combat: Option<Combat>,
+    level_up: Option<LevelUp>,
+    rooms: Vec<Room>,
+    stats: RunStats,
+}
+
+/// Writes the whole run to `path` as JSON.
+pub fn save_game(game: &Game, path: &Path) -> io::Result<()> {
+    // The current room is a working copy, so it wins over the stored one
+    let mut rooms: Vec<Room> = game
+        .rooms
+        .values()
+        .filter(|room| room.grid_position != game.current_room.grid_position)
+        .cloned()
+        .collect();
+    rooms.push(game.current_room.clone());
+    rooms.sort_by_key(|room| (room.grid_position.x, room.grid_position.y));
+
+    let save = SaveFile {
+        version: SAVE_VERSION,
+        difficulty: game.balance.difficulty,
+        balance: game.balance.fingerprint(),
+        seed: game.seed,
+        rng: game.rng.clone(),
+        player: game.player.clone(),
+        current_room: game.current_room.grid_position,
+        fight_prompt: game.fight_prompt,
+        combat: game.combat.clone(),
+        level_up: game.level_up,
+        rooms,
+        stats: game.stats.clone(),
+    };
+    let content = serde_json::to_string_pretty(&save).map_err(Error::other)?;
+    fs::write(path, content)
+}
+
+/// Restores a saved run, which has to go on with the `balance` it was saved with.
+pub fn load_game(path: &Path, balance: Balance) -> Result<Game> {
+    let invalid = |reason: String| AdventureError::InvalidSave {
+        path: path.to_path_buf(),
+        reason,
+    };
+    balance.validate().map_err(AdventureError::InvalidBalance)?;
+    let content = fs::read_to_string(path).map_err(AdventureError::file("load", path))?;
+    let save: SaveFile = serde_json::from_str(&content).map_err(|err| invalid(err.to_string()))?;
+    if save.version != SAVE_VERSION {
+        return Err(invalid(format!(
+            "unsupported version {}, expected {}",
+            save.version, SAVE_VERSION
+        )));
+    }
+    balance
+        .check_matches(save.difficulty, save.balance, "saved")
+        .map_err(AdventureError::file("load", path))?;
+    check_player(&save.player).map_err(invalid)?;
+
+    let rooms: HashMap<RoomPosition, Room> = save
+        .rooms
+        .into_iter()
+        .map(|room| (room.grid_position, room))
+        .collect();
+    let current_room = rooms
+        .get(&save.current_room)
+        .cloned()
+        .ok_or_else(|| invalid(String::from("the current room is missing")))?;
+
+    Ok(Game {
+        rooms,
+        player: save.player,
+        current_room,
+        fight_prompt: save.fight_prompt,
+        combat: save.combat,
+        level_up: save.level_up,
+        inventory_cursor: None,
+        shop: None,
+        seed: save.seed,
+        rng: save.rng,
+        stats: save.stats,
+        balance,
+        events: Vec::new(),
+    })
+}
+
+/// Rejects player state the game never produces and would trip over, like health above the
+/// maximum.
+fn check_player(player: &Player) -> std::result::Result<(), String> {
+    if player.level == 0 {
+        return Err(String::from("the player is on level 0"));
+    }
+    if player.health() > player.max_health() {
+        return Err(format!(
+            "the player has {} health, more than the maximum of {}",
+            player.health(),
+            player.max_health()
+        ));
+    }
+    let position = player.position;
+    if !(2..=11).contains(&position.x) || !(2..=6).contains(&position.y) {
+        return Err(format!(
+            "the player stands outside the room at {},{}",
+            position.x, position.y
+        ));
+    }
+    if player.inventory.bag.len() > BAG_SIZE {
+        return Err(format!(
+            "the bag holds {} items, more than {BAG_SIZE}",
+            player.inventory.bag.len()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameAction;
+    use std::path::PathBuf;
+
+    fn save_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("adventure-{}-{name}.sav", std::process::id()))
+    }
+
+    fn played_game() -> Game {
+        let mut game = Game::start(7, Balance::default()).unwrap();
+        for action in [
+            GameAction::MoveDown,
+            GameAction::MoveRight,
+            GameAction::MoveRight,
+            GameAction::MoveDown,
+        ] {
+            game.apply(action);
+        }
+        game
+    }
+
+    #[test]
+    fn loading_a_save_resumes_the_same_run() {
+        let mut game = played_game();
+        let path = save_path("round-trip");
+        save_game(&game, &path).unwrap();
+        let mut loaded = load_game(&path, Balance::default()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.seed, game.seed);
+        assert_eq!(loaded.rng, game.rng);
+        assert!(loaded.player == game.player);
+        assert_eq!(loaded.current_room, game.current_room);
+        // The save stores the current room with the others
+        game.save_current_room();
+        assert_eq!(loaded.rooms, game.rooms);
+        assert_eq!(loaded.fight_prompt, game.fight_prompt);
+        assert_eq!(loaded.combat, game.combat);
+        // Both copies play on the same way
+        for action in [
+            GameAction::MoveLeft,
+            GameAction::Confirm,
+            GameAction::Attack,
+        ] {
+            assert_eq!(loaded.apply(action), game.apply(action));
+        }
+    }
+
+    #[test]
+    fn saves_with_impossible_health_are_refused() {
+        let game = played_game();
+        let path = save_path("health");
+        save_game(&game, &path).unwrap();
+        let mut save: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        save["player"]["health"] = serde_json::json!(500);
+        fs::write(&path, save.to_string()).unwrap();
+        let result = load_game(&path, Balance::default());
+        fs::remove_file(&path).unwrap();
+
+        let Err(AdventureError::InvalidSave { reason, .. }) = result else {
+            panic!("Expected the save to be refused");
+        };
+        assert_eq!(
+            reason,
+            "the player has 500 health, more than the maximum of 100"
+        );
+    }
+
+    #[test]
+    fn saves_only_resume_on_their_own_balance() {
+        let game = played_game();
+        let path = save_path("balance");
+        save_game(&game, &path).unwrap();
+        let hard = Balance {
+            difficulty: Difficulty::Hard,
+            ..Balance::default()
+        };
+        let tuned = Balance {
+            flee_probability: 0.9,
+            ..Balance::default()
+        };
+        let results = [load_game(&path, hard), load_game(&path, tuned)];
+        fs::remove_file(&path).unwrap();
+
+        let messages: Vec<String> = results
+            .into_iter()
+            .map(|result| match result {
+                Err(AdventureError::File { source, .. }) => source.to_string(),
+                _ => panic!("Expected the save to be refused"),
+            })
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "saved on normal difficulty, not hard",
+                "saved with a different balance config"
+            ]
+        );
+    }
+}
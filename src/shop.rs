@@ -0,0 +1,96 @@
+//! Merchants and trading with them.
+
+use super::consts::Position;
+use super::helper::select_random_weighted;
+use super::inventory::Item;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Items a merchant starts out with.
+const STOCK_SIZE: usize = 4;
+/// Prices go up by this share of the base price for every room away from the origin.
+const PRICE_GROWTH: f32 = 0.25;
+
+/// A trader standing in a room, selling from a small stock and buying whatever the player
+/// brings along.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Merchant {
+    /// Tile the merchant stands on.
+    pub position: Position,
+    /// Items left for sale.
+    pub stock: Vec<Item>,
+    /// Rooms between the merchant and the origin, which sets the prices.
+    pub distance: u16,
+}
+
+impl Merchant {
+    /// A merchant stocked with what can be found `distance` rooms out.
+    pub fn generate(position: Position, distance: u16, rng: &mut impl Rng) -> Merchant {
+        let table = Item::spawn_table(distance);
+        let stock = (0..STOCK_SIZE)
+            .map(|_| *select_random_weighted(&table, rng))
+            .collect();
+        Merchant {
+            position,
+            stock,
+            distance,
+        }
+    }
+
+    /// What the merchant asks for `item`.
+    pub fn buy_price(&self, item: Item) -> u32 {
+        let scale = 1.0 + PRICE_GROWTH * self.distance as f32;
+        (item.base_price() as f32 * scale).round() as u32
+    }
+
+    /// What the merchant pays for `item`, half of what they would ask for it.
+    pub fn sell_price(&self, item: Item) -> u32 {
+        (self.buy_price(item) / 2).max(1)
+    }
+}
+
+/// Which list the shop screen shows.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ShopMode {
+    /// Browsing the merchant's stock.
+    #[default]
+    Buy,
+    /// Browsing the player's bag.
+    Sell,
+}
+
+/// State of the shop screen while it is open.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Shop {
+    /// Whether the player is buying or selling.
+    pub mode: ShopMode,
+    /// Selected slot of the stock or the bag, depending on the mode.
+    pub cursor: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merchant(distance: u16) -> Merchant {
+        Merchant {
+            position: Position { x: 5, y: 4 },
+            stock: vec![Item::Sword],
+            distance,
+        }
+    }
+
+    #[test]
+    fn prices_grow_with_distance() {
+        assert_eq!(merchant(0).buy_price(Item::Sword), 40);
+        assert_eq!(merchant(4).buy_price(Item::Sword), 80);
+        assert!(merchant(4).buy_price(Item::Potion) > merchant(1).buy_price(Item::Potion));
+    }
+
+    #[test]
+    fn merchants_buy_for_half_the_price() {
+        let merchant = merchant(2);
+        assert_eq!(merchant.sell_price(Item::Sword), 30);
+        assert_eq!(merchant.sell_price(Item::Potion), 7);
+    }
+}
@@ -0,0 +1,272 @@
+//! The text box below the room for prompts, fights and menus.
+
+use crate::combat::{Combat, LOG_LINES};
+use crate::config::{DIALOG_COLUMN, DIALOG_LINE};
+use crate::enemy::Enemy;
+use crate::error::{RenderContext, Result};
+use crate::game::{FightPrompt, Game, LevelUp};
+use crate::inventory::Item;
+use crate::shop::{Shop, ShopMode};
+use crate::ui::surface::Surface;
+
+pub struct Dialog {}
+
+impl Dialog {
+    /// Lines of the dialog `game` waits on, the most pressing one when several are open. Empty
+    /// when the player is exploring.
+    pub fn content(game: &Game) -> Vec<String> {
+        if game.is_over() {
+            Dialog::get_game_over_dialog_content(game)
+        } else if let Some(level_up) = game.level_up {
+            Dialog::get_level_up_dialog_content(game, level_up)
+        } else if let Some(cursor) = game.inventory_cursor {
+            Dialog::get_inventory_dialog_content(game, cursor)
+        } else if let Some(shop) = game.shop {
+            Dialog::get_shop_dialog_content(game, shop)
+        } else if let Some(combat) = &game.combat {
+            Dialog::get_combat_dialog_content(game, combat)
+        } else if let FightPrompt::Open(enemy) = game.fight_prompt {
+            Dialog::get_fight_dialog_content(game, enemy)
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn show(content: &[String], surface: &mut dyn Surface) -> Result<()> {
+        let Some(len) = content.iter().map(|str| str.len() as u16).max() else {
+            return Ok(());
+        };
+        Dialog::draw_top(len, surface)?;
+        let num_lines = content.len() as u16;
+        Dialog::draw_content(content, len, num_lines, surface)?;
+        Dialog::draw_bottom(len, num_lines, surface)
+    }
+
+    fn draw_top(content_length: u16, surface: &mut dyn Surface) -> Result<()> {
+        let mut top = String::new();
+        for _ in 0..(content_length + 4) {
+            top.push('▒');
+        }
+
+        surface
+            .draw(DIALOG_COLUMN, DIALOG_LINE, &top)
+            .drawing("the top of the dialog")
+    }
+
+    fn draw_content(
+        content: &[String],
+        len: u16,
+        num_lines: u16,
+        surface: &mut dyn Surface,
+    ) -> Result<()> {
+        Dialog::draw_margin(len, DIALOG_LINE + 1, surface)?;
+        for (index, line) in content.iter().enumerate() {
+            let num_padding_chars = len - line.len() as u16;
+            let padding = (0..num_padding_chars).map(|_| " ").collect::<String>();
+            surface
+                .draw(
+                    DIALOG_COLUMN,
+                    DIALOG_LINE + 2 + index as u16,
+                    &format!("▒ {line}{padding} ▒"),
+                )
+                .drawing("the dialog content")?;
+        }
+        Dialog::draw_margin(len, DIALOG_LINE + 2 + num_lines, surface)
+    }
+
+    fn draw_margin(len: u16, line: u16, surface: &mut dyn Surface) -> Result<()> {
+        let mut margin = String::from("▒ ");
+
+        for _ in 0..len {
+            margin.push(' ');
+        }
+
+        margin.push_str(" ▒");
+        surface
+            .draw(DIALOG_COLUMN, line, &margin)
+            .drawing("the dialog margin")
+    }
+
+    fn draw_bottom(content_length: u16, num_lines: u16, surface: &mut dyn Surface) -> Result<()> {
+        let mut bottom = String::new();
+        for _ in 0..(content_length + 4) {
+            bottom.push('▒')
+        }
+        surface
+            .draw(DIALOG_COLUMN, DIALOG_LINE + 2 + num_lines + 1, &bottom)
+            .drawing("the bottom of the dialog")
+    }
+
+    /// Blanks the area the dialog showing `lines` covered.
+    pub fn clear(lines: &[String], surface: &mut dyn Surface) -> Result<()> {
+        let num_lines = 2 + lines.len() + 2; // Top margin + content + bottom margin
+        let Some(max_line_len) = lines.iter().map(|str| str.len()).max() else {
+            return Ok(());
+        };
+        let max_line_len = max_line_len + 4; // Acounting for margins
+
+        let clear_line = (0..max_line_len).map(|_| " ").collect::<String>();
+
+        for i in 0..num_lines {
+            surface
+                .draw(DIALOG_COLUMN, DIALOG_LINE + i as u16, &clear_line)
+                .drawing("over the dialog")?;
+        }
+        Ok(())
+    }
+
+    // Fight dialogs
+    fn get_fight_dialog_content(game: &Game, enemy: Enemy) -> Vec<String> {
+        let player = &game.player;
+        let flee_chance = enemy.flee_probability(game.balance.flee_probability) * 100.0;
+        let name = enemy.kind.name();
+        let line1 = if enemy.provoked {
+            format!("The {name} on level {} remembers you", enemy.level)
+        } else {
+            format!("Do you want to fight a {name} on level {}", enemy.level)
+        };
+        // The chances combat rolls every round, not a single win chance that nothing rolls
+        let combat = Combat::new(enemy);
+        let line2 = format!(
+            "Your level is {}, you hit {:.0}% and it hits {:.0}% of the time",
+            player.level,
+            combat.player_hit_chance(player, &game.balance) * 100.0,
+            combat.enemy_hit_chance(player, &game.balance) * 100.0
+        );
+        let line3 = if enemy.provoked {
+            format!("Y - start combat, F - flee ({flee_chance:.0}% chance)")
+        } else {
+            format!("Y - start combat, N - decline, F - flee ({flee_chance:.0}% chance)")
+        };
+
+        let description = enemy.kind.enemy_trait().description().to_string();
+
+        vec![line1, description, line2, line3]
+    }
+
+    // Combat dialog
+    fn get_combat_dialog_content(game: &Game, combat: &Combat) -> Vec<String> {
+        let player = &game.player;
+        let enemy = &combat.enemy;
+        let mut content = vec![
+            format!(
+                "Fighting a {} on level {}, HP {}/{}",
+                enemy.kind.name(),
+                enemy.level,
+                enemy.health,
+                enemy.max_health
+            ),
+            format!(
+                "Your HP {}/{}, hit chance {:.0}%, potions {}",
+                player.health(),
+                player.max_health(),
+                combat.player_hit_chance(player, &game.balance) * 100.0,
+                player.inventory.count(Item::Potion)
+            ),
+            String::new(),
+        ];
+        let log = combat.latest_log();
+        content.extend(log.iter().cloned());
+        content.extend((log.len()..LOG_LINES).map(|_| String::new()));
+        content.push(String::new());
+        content.push(String::from("1 - attack, 2 - defend, 3 - potion, 4 - flee"));
+        content
+    }
+
+    // Inventory dialog
+    fn get_inventory_dialog_content(game: &Game, cursor: usize) -> Vec<String> {
+        let inventory = &game.player.inventory;
+        let worn = |item: Option<Item>| {
+            item.map_or_else(|| String::from("none"), |item| item.description())
+        };
+        let mut content = vec![
+            format!("Weapon: {}", worn(inventory.weapon)),
+            format!("Armour: {}", worn(inventory.armour)),
+            String::new(),
+        ];
+        if inventory.bag.is_empty() {
+            content.push(String::from("Your bag is empty"));
+        }
+        for (index, item) in inventory.bag.iter().enumerate() {
+            let marker = if index == cursor { ">" } else { " " };
+            content.push(format!("{marker} {}", item.description()));
+        }
+        content.push(String::new());
+        content.push(String::from("Up/Down - select, Enter - use, I - close"));
+        content
+    }
+
+    // Shop dialog
+    fn get_shop_dialog_content(game: &Game, shop: Shop) -> Vec<String> {
+        let Some(merchant) = &game.current_room.merchant else {
+            return Vec::new();
+        };
+        let (title, empty, confirm) = match shop.mode {
+            ShopMode::Buy => ("Buying", "Sold out", "Enter - buy"),
+            ShopMode::Sell => ("Selling", "Nothing you can sell", "Enter - sell"),
+        };
+        let mut content = vec![
+            format!("{title}, you have {} gold", game.player.inventory.gold),
+            String::new(),
+        ];
+        let items = game.shop_items();
+        if items.is_empty() {
+            content.push(String::from(empty));
+        }
+        for (index, item) in items.iter().enumerate() {
+            let marker = if index == shop.cursor { ">" } else { " " };
+            let price = match shop.mode {
+                ShopMode::Buy => merchant.buy_price(*item),
+                ShopMode::Sell => merchant.sell_price(*item),
+            };
+            content.push(format!("{marker} {} - {price} gold", item.description()));
+        }
+        content.push(String::new());
+        content.push(format!(
+            "Up/Down - select, {confirm}, Left/Right - switch, N - leave"
+        ));
+        content
+    }
+
+    // Level up dialog
+    fn get_level_up_dialog_content(game: &Game, level_up: LevelUp) -> Vec<String> {
+        let player = &game.player;
+        let title = if level_up.to - level_up.from > 1 {
+            format!("Level up! Level {} -> {}", level_up.from, level_up.to)
+        } else {
+            format!("Level up! You reached level {}", level_up.to)
+        };
+        vec![
+            title,
+            format!(
+                "Max HP {}, attack {}, defense {}",
+                player.max_health(),
+                player.attack(),
+                player.defense()
+            ),
+            format!(
+                "XP {}/{} to the next level",
+                player.xp,
+                game.balance.xp_to_next_level(player.level)
+            ),
+            String::from("Enter - continue"),
+        ]
+    }
+
+    // Game over dialog
+    fn get_game_over_dialog_content(game: &Game) -> Vec<String> {
+        let cause = game
+            .stats
+            .cause_of_death
+            .clone()
+            .unwrap_or_else(|| String::from("Unknown"));
+        vec![
+            String::from("You died"),
+            format!("Cause of death: {cause}"),
+            format!("Rooms explored: {}", game.rooms.len()),
+            format!("Enemies defeated: {}", game.stats.enemies_defeated),
+            format!("Max level: {}", game.stats.max_level),
+            String::from("Restart with the same seed? Y/n (Q to quit)"),
+        ]
+    }
+}
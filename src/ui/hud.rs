@@ -0,0 +1,70 @@
+//! The status panel next to the room.
+
+use crate::config::{HUD_COLUMN, HUD_LINE, HUD_WIDTH};
+use crate::error::{RenderContext, Result};
+use crate::game::Game;
+use crate::ui::surface::{Color, Surface};
+
+const HEALTH_BAR_WIDTH: u16 = 10;
+
+/// Player status, drawn right of the room.
+pub struct Hud {}
+
+impl Hud {
+    pub fn draw(game: &Game, surface: &mut dyn Surface) -> Result<()> {
+        let player = &game.player;
+        let room = &game.current_room;
+        let lines = [
+            format!(
+                "HP {} {}/{}",
+                " ".repeat(HEALTH_BAR_WIDTH as usize),
+                player.health(),
+                player.max_health()
+            ),
+            format!(
+                "Level {}  XP {}/{}",
+                player.level,
+                player.xp,
+                game.balance.xp_to_next_level(player.level)
+            ),
+            format!("Gold {}", player.inventory.gold),
+            format!("Room {},{}", room.grid_position.x, room.grid_position.y),
+            format!("Rooms discovered {}", game.rooms.len()),
+            format!("Enemies in room {}", room.enemies.len()),
+        ];
+        for (index, line) in lines.iter().enumerate() {
+            // Padded so a shorter line covers the previous one
+            surface
+                .draw(
+                    HUD_COLUMN,
+                    HUD_LINE + index as u16,
+                    &format!("{line:<width$}", width = HUD_WIDTH as usize),
+                )
+                .drawing("the HUD")?;
+        }
+        Hud::draw_health_bar(game, surface)
+    }
+
+    /// Fills the gap left in the first line, coloured by how hurt the player is.
+    fn draw_health_bar(game: &Game, surface: &mut dyn Surface) -> Result<()> {
+        let player = &game.player;
+        let ratio = player.health() as f32 / player.max_health() as f32;
+        // Round up so any health left shows at least one cell
+        let filled = (ratio * HEALTH_BAR_WIDTH as f32).ceil() as usize;
+        let bar = format!(
+            "{}{}",
+            "█".repeat(filled),
+            "░".repeat(HEALTH_BAR_WIDTH as usize - filled)
+        );
+        let color = if ratio > 0.5 {
+            Color::Green
+        } else if ratio > 0.25 {
+            Color::Yellow
+        } else {
+            Color::Red
+        };
+        surface
+            .draw_colored(HUD_COLUMN + 3, HUD_LINE, &bar, color)
+            .drawing("the health bar")
+    }
+}
@@ -1,9 +1,12 @@
-use crate::libs::consts::Door;
-use crate::libs::game::Game;
-use crate::libs::room::{Room, RoomPosition};
+//! The map of discovered rooms.
+
+use crate::config::MINIMAP_COLUMN;
+use crate::consts::Door;
+use crate::error::{RenderContext, Result};
+use crate::game::Game;
+use crate::room::{Room, RoomPosition};
+use crate::ui::surface::Surface;
 use std::collections::HashMap;
-use std::io::{Stdout, Write};
-use termion::raw::RawTerminal;
 
 pub struct Minimap {}
 
@@ -11,12 +14,12 @@ impl Minimap {
     pub fn print_minimap(
         rooms: &HashMap<RoomPosition, Room>,
         game: &Game,
-        stdout: &mut RawTerminal<Stdout>,
-    ) {
+        surface: &mut dyn Surface,
+    ) -> Result<()> {
         if rooms.is_empty() {
-            write!(stdout, "{}Empty map", termion::cursor::Goto(15, 1))
-                .expect("Failed to move to start of map");
-            return;
+            return surface
+                .draw(MINIMAP_COLUMN, 2, "Empty map")
+                .drawing("the empty map");
         }
         let mut curr_line = 2;
         let (min_x, max_x, min_y, max_y) = Minimap::get_map_bounds(rooms);
@@ -27,10 +30,10 @@ impl Minimap {
             for x in min_x..=max_x {
                 let pos = RoomPosition { x, y };
                 if let Some(room) = rooms.get(&pos) {
-                    if room == &game.current_room {
-                        line.push_str("█");
+                    if room.grid_position == game.current_room.grid_position {
+                        line.push('█');
                     } else {
-                        line.push_str("░");
+                        line.push('░');
                     }
 
                     let doors = &room.doors;
@@ -38,19 +41,20 @@ impl Minimap {
                         && doors.contains(&Door::RIGHT)
                         && rooms
                             .get(&RoomPosition { x: x + 1, y })
-                            .map_or(false, |r| r.doors.contains(&Door::LEFT))
+                            .is_some_and(|r| r.doors.contains(&Door::LEFT))
                     {
                         // print!("↔");
-                        line.push_str("-");
+                        line.push('-');
                     } else {
-                        line.push_str(" ");
+                        line.push(' ');
                     }
                 } else {
                     line.push_str("  ");
                 }
             }
-            write!(stdout, "{}{line}", termion::cursor::Goto(15, curr_line))
-                .expect("Failed to move to next line");
+            surface
+                .draw(MINIMAP_COLUMN, curr_line, &line)
+                .drawing("the minimap")?;
             curr_line += 1;
             line = String::new();
             // Print vertical connections
@@ -60,21 +64,23 @@ impl Minimap {
                     let pos_below = RoomPosition { x, y: y - 1 };
                     if rooms
                         .get(&pos)
-                        .map_or(false, |r| r.doors.contains(&Door::BOTTOM))
+                        .is_some_and(|r| r.doors.contains(&Door::BOTTOM))
                         && rooms
                             .get(&pos_below)
-                            .map_or(false, |r| r.doors.contains(&Door::TOP))
+                            .is_some_and(|r| r.doors.contains(&Door::TOP))
                     {
                         line.push_str("| ");
                     } else {
                         line.push_str("  ");
                     }
                 }
-                write!(stdout, "{}{line}", termion::cursor::Goto(15, curr_line))
-                    .expect("Failed to move to next line");
+                surface
+                    .draw(MINIMAP_COLUMN, curr_line, &line)
+                    .drawing("the minimap")?;
                 curr_line += 1;
             }
         }
+        Ok(())
     }
 
     fn get_map_bounds(rooms: &HashMap<RoomPosition, Room>) -> (i8, i8, i8, i8) {
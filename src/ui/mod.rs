@@ -0,0 +1,9 @@
+//! Drawing a game: the renderer, the pieces it draws and the surfaces it draws on.
+
+mod dialog;
+pub(crate) mod hud;
+pub(crate) mod minimap;
+pub mod renderer;
+pub mod surface;
+#[cfg(feature = "terminal")]
+pub mod terminal;
@@ -0,0 +1,341 @@
+//! Turns a game and its events into drawing calls.
+
+use crate::consts::{Door, DoorState, Position};
+use crate::enemy::{Enemy, EnemyKind};
+use crate::error::{RenderContext, Result};
+use crate::event::{GameEvent, TradeRefusal};
+use crate::game::Game;
+use crate::inventory::{Item, ItemKind, Loot, Pickup};
+use crate::room::{HealingKind, HealingSpot, Room};
+use crate::shop::Merchant;
+use crate::ui::dialog::Dialog;
+use crate::ui::hud::Hud;
+use crate::ui::minimap::Minimap;
+use crate::ui::surface::{Color, Surface};
+
+/// Draws a game on a surface and keeps it up to date from the events the game reports.
+#[derive(Default)]
+pub struct Renderer {
+    /// Lines of the dialog currently on screen, so it can be cleared once the game moves on.
+    dialog: Vec<String>,
+}
+
+impl Renderer {
+    /// A renderer that hasn't drawn anything yet.
+    pub fn new() -> Renderer {
+        Renderer::default()
+    }
+
+    /// Draws everything from scratch.
+    pub fn draw_screen(&mut self, game: &Game, surface: &mut dyn Surface) -> Result<()> {
+        surface.clear().drawing("the screen")?;
+
+        Renderer::draw_seed(game, surface)?;
+        Hud::draw(game, surface)?;
+        Minimap::print_minimap(&game.rooms, game, surface)?;
+
+        Renderer::draw_room(&game.current_room, game.player.position, surface)?;
+        self.dialog = Dialog::content(game);
+        Dialog::show(&self.dialog, surface)?;
+        surface.flush().drawing("the screen")
+    }
+
+    /// Draws what changed with `events`, which `game` has already been through.
+    pub fn render(
+        &mut self,
+        game: &Game,
+        events: &[GameEvent],
+        surface: &mut dyn Surface,
+    ) -> Result<()> {
+        let room = &game.current_room;
+        // Notices share one line, only the last one stays readable
+        let mut notice = None;
+        for event in events {
+            match event {
+                GameEvent::PlayerMoved { from, to } => {
+                    Renderer::draw_floor(room, *from, surface)?;
+                    surface
+                        .draw(to.x as u16, to.y as u16, "&")
+                        .drawing("the player")?;
+                }
+                GameEvent::RoomEntered { .. } => {
+                    Renderer::draw_room(room, game.player.position, surface)?;
+                }
+                GameEvent::DoorLocked { key, .. } => {
+                    notice = Some(format!(
+                        "The door is locked, it needs {}",
+                        Item::Key(*key).label()
+                    ));
+                }
+                GameEvent::DoorUnlocked { key, .. } => {
+                    notice = Some(format!(
+                        "You unlock the door with {}",
+                        Item::Key(*key).label()
+                    ));
+                }
+                GameEvent::DoorSealed { .. } => {
+                    notice = Some(String::from("The door is sealed until the room is cleared"));
+                }
+                GameEvent::DoorsUnsealed => {
+                    Renderer::draw_doors(room, surface)?;
+                    if let Some(notice) = &mut notice {
+                        notice.push_str(", the sealed doors open");
+                    }
+                }
+                GameEvent::HealingSpotUsed { kind } => {
+                    notice = Some(format!("The {} restores your health", kind.name()));
+                }
+                GameEvent::LootPickedUp { loot } => {
+                    notice = Some(format!("Picked up {}", loot.name()));
+                }
+                GameEvent::LootLeftBehind { loot } => {
+                    notice = Some(format!("No room in your bag for {}", loot.name()));
+                }
+                GameEvent::EnemyMoved { enemy, from } => {
+                    Renderer::draw_floor(room, *from, surface)?;
+                    Renderer::draw_enemy(enemy, surface)?;
+                }
+                GameEvent::DeclineRefused { .. } => {
+                    notice = Some(String::from("It won't let you go, fight or flee"));
+                }
+                GameEvent::Escaped { .. } => notice = Some(String::from("You got away")),
+                GameEvent::FightWon { enemy, xp, loot } => {
+                    Renderer::draw_floor(room, enemy.position, surface)?;
+                    notice = Some(match loot {
+                        Some(loot) => format!(
+                            "+{xp} XP, the {} dropped {}",
+                            enemy.kind.name(),
+                            loot.name()
+                        ),
+                        None => format!("+{xp} XP"),
+                    });
+                }
+                GameEvent::PotionDrunk { healed } => {
+                    notice = Some(format!("You drink a potion and heal {healed}"));
+                }
+                GameEvent::ItemEquipped { item } => {
+                    notice = Some(format!("You equip the {}", item.name()));
+                }
+                GameEvent::ItemNotUsable { .. } => {
+                    notice = Some(String::from("Walk into a locked door to use a key"));
+                }
+                GameEvent::ItemBought { item, price } => {
+                    notice = Some(format!("You buy the {} for {price} gold", item.name()));
+                }
+                GameEvent::ItemSold { item, price } => {
+                    notice = Some(format!("You sell the {} for {price} gold", item.name()));
+                }
+                GameEvent::TradeRefused { item, reason } => {
+                    notice = Some(match reason {
+                        TradeRefusal::TooExpensive => {
+                            format!("You can't afford the {}", item.name())
+                        }
+                        TradeRefusal::BagFull => String::from("Your bag is full"),
+                    });
+                }
+                GameEvent::GameRestarted { .. } => return self.draw_screen(game, surface),
+                // Only change what the dialog shows, which is synced below
+                GameEvent::FightOffered { .. }
+                | GameEvent::FightDeclined { .. }
+                | GameEvent::FightStarted { .. }
+                | GameEvent::DamageDealt { .. }
+                | GameEvent::DamageTaken { .. }
+                | GameEvent::LevelledUp { .. }
+                | GameEvent::LevelUpAcknowledged
+                | GameEvent::PlayerDied { .. }
+                | GameEvent::InventoryOpened
+                | GameEvent::InventoryClosed
+                | GameEvent::SelectionMoved { .. }
+                | GameEvent::ShopOpened
+                | GameEvent::ShopClosed
+                | GameEvent::ShopModeSwitched { .. } => {}
+            }
+        }
+        if let Some(notice) = notice {
+            Renderer::draw_notice(game, &notice, surface)?;
+        }
+        self.show_dialog(game, surface)
+    }
+
+    /// Replaces the dialog on screen with the one `game` waits on, if they differ.
+    fn show_dialog(&mut self, game: &Game, surface: &mut dyn Surface) -> Result<()> {
+        let content = Dialog::content(game);
+        if content == self.dialog {
+            return Ok(());
+        }
+        Dialog::clear(&self.dialog, surface)?;
+        Dialog::show(&content, surface)?;
+        self.dialog = content;
+        Ok(())
+    }
+
+    /// Draws the seed of the run above the HUD.
+    pub fn draw_seed(game: &Game, surface: &mut dyn Surface) -> Result<()> {
+        surface
+            .draw(15, 1, &format!("Seed: {}", game.seed))
+            .drawing("the seed")
+    }
+
+    /// Shows `notice` next to the seed, replacing the one shown before.
+    pub fn draw_notice(game: &Game, notice: &str, surface: &mut dyn Surface) -> Result<()> {
+        let column = 15 + format!("Seed: {}", game.seed).len() as u16 + 2;
+        surface
+            .clear_line_from(column, 1)
+            .and_then(|_| surface.draw(column, 1, notice))
+            .drawing("a notice")
+    }
+
+    /// Draws the walls, the doors and everything in `room`, with the player at `player`.
+    pub fn draw_room(room: &Room, player: Position, surface: &mut dyn Surface) -> Result<()> {
+        Renderer::draw_walls(surface)?;
+        Renderer::draw_doors(room, surface)?;
+        if let Some(healing) = &room.healing {
+            Renderer::draw_healing_spot(healing, surface)?;
+        }
+        for pickup in &room.pickups {
+            Renderer::draw_pickup(pickup, surface)?;
+        }
+        if let Some(merchant) = &room.merchant {
+            Renderer::draw_merchant(merchant, surface)?;
+        }
+        surface
+            .draw(player.x as u16, player.y as u16, "&")
+            .drawing("the player")?;
+        for enemy in &room.enemies {
+            Renderer::draw_enemy(enemy, surface)?;
+        }
+        surface.flush().drawing("the room")
+    }
+
+    fn draw_walls(surface: &mut dyn Surface) -> Result<()> {
+        surface.draw(1, 1, "████████████").drawing("the top wall")?;
+        for r in 0..5 {
+            surface
+                .draw(1, 2 + r, "█          █")
+                .drawing("the side walls")?;
+        }
+        surface
+            .draw(1, 7, "████████████")
+            .drawing("the bottom wall")
+    }
+
+    /// Draws the gaps of the doors, filled in with bars while locked or sealed.
+    pub fn draw_doors(room: &Room, surface: &mut dyn Surface) -> Result<()> {
+        for door in &room.doors {
+            let (glyph, color) = match room.door_state(*door) {
+                DoorState::Open => (" ", None),
+                DoorState::Locked(_) => ("+", Some(Color::Yellow)),
+                DoorState::Sealed => ("#", Some(Color::Red)),
+            };
+            let what = match door {
+                Door::TOP => "the top door",
+                Door::RIGHT => "the right door",
+                Door::BOTTOM => "the bottom door",
+                Door::LEFT => "the left door",
+            };
+            for (x, y) in door_tiles(*door) {
+                let result = match color {
+                    Some(color) => surface.draw_colored(x, y, glyph, color),
+                    None => surface.draw(x, y, glyph),
+                };
+                result.drawing(what)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Redraws the floor at `position` once whoever stood there has left.
+    pub fn draw_floor(room: &Room, position: Position, surface: &mut dyn Surface) -> Result<()> {
+        if let Some(pickup) = room.get_pickup_at_position(position) {
+            return Renderer::draw_pickup(&pickup, surface);
+        }
+        match room.healing {
+            Some(healing) if healing.position == position => {
+                Renderer::draw_healing_spot(&healing, surface)
+            }
+            _ => surface
+                .draw(position.x as u16, position.y as u16, " ")
+                .drawing("the floor"),
+        }
+    }
+
+    fn draw_enemy(enemy: &Enemy, surface: &mut dyn Surface) -> Result<()> {
+        let (glyph, color) = enemy_look(enemy.kind);
+        surface
+            .draw_colored(
+                enemy.position.x as u16,
+                enemy.position.y as u16,
+                glyph,
+                color,
+            )
+            .drawing("an enemy")
+    }
+
+    fn draw_pickup(pickup: &Pickup, surface: &mut dyn Surface) -> Result<()> {
+        let (glyph, color) = loot_look(pickup.loot);
+        surface
+            .draw_colored(
+                pickup.position.x as u16,
+                pickup.position.y as u16,
+                glyph,
+                color,
+            )
+            .drawing("an item")
+    }
+
+    fn draw_healing_spot(healing: &HealingSpot, surface: &mut dyn Surface) -> Result<()> {
+        let (x, y) = (healing.position.x as u16, healing.position.y as u16);
+        let result = match (healing.used, healing.kind) {
+            (true, _) => surface.draw(x, y, "."),
+            (false, HealingKind::Shrine) => surface.draw_colored(x, y, "†", Color::White),
+            (false, HealingKind::Fountain) => surface.draw_colored(x, y, "~", Color::Cyan),
+        };
+        result.drawing("the healing spot")
+    }
+
+    fn draw_merchant(merchant: &Merchant, surface: &mut dyn Surface) -> Result<()> {
+        surface
+            .draw_colored(
+                merchant.position.x as u16,
+                merchant.position.y as u16,
+                "M",
+                Color::Yellow,
+            )
+            .drawing("the merchant")
+    }
+}
+
+fn enemy_look(kind: EnemyKind) -> (&'static str, Color) {
+    match kind {
+        EnemyKind::Slime => ("o", Color::Green),
+        EnemyKind::Skeleton => ("§", Color::White),
+        EnemyKind::Archer => ("}", Color::Yellow),
+        EnemyKind::Boss => ("Ω", Color::Red),
+    }
+}
+
+fn loot_look(loot: Loot) -> (&'static str, Color) {
+    match loot {
+        Loot::Gold(_) => ("$", Color::Yellow),
+        Loot::Item(item) => item_look(item),
+    }
+}
+
+fn item_look(item: Item) -> (&'static str, Color) {
+    match item.kind() {
+        ItemKind::Potion => ("!", Color::Magenta),
+        ItemKind::Weapon => (")", Color::White),
+        ItemKind::Armour => ("[", Color::Cyan),
+        ItemKind::Key => ("k", Color::Yellow),
+    }
+}
+
+/// Screen tiles of the gap a door leaves in the wall.
+fn door_tiles(door: Door) -> Vec<(u16, u16)> {
+    match door {
+        Door::TOP => (4..10).map(|x| (x, 1)).collect(),
+        Door::BOTTOM => (4..10).map(|x| (x, 7)).collect(),
+        Door::RIGHT => (3..6).map(|y| (12, y)).collect(),
+        Door::LEFT => (3..6).map(|y| (1, y)).collect(),
+    }
+}
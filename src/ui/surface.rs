@@ -0,0 +1,284 @@
+//! Things to draw on: the terminal or an in-memory grid.
+
+use std::fmt;
+use std::io::Result;
+#[cfg(feature = "terminal")]
+use std::io::Write;
+
+/// Foreground colors the renderer uses, mapped to the terminal's own palette.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Color {
+    /// The terminal's green.
+    Green,
+    /// The terminal's white.
+    White,
+    /// The terminal's yellow.
+    Yellow,
+    /// The terminal's red.
+    Red,
+    /// The terminal's cyan.
+    Cyan,
+    /// The terminal's magenta.
+    Magenta,
+}
+
+/// Something the renderer can draw text on, cell by cell.
+pub trait Surface {
+    /// Writes `text` starting at the 1-based terminal cell (`x`, `y`).
+    fn draw(&mut self, x: u16, y: u16, text: &str) -> Result<()>;
+    /// Like `draw`, in `color` where the surface supports it.
+    fn draw_colored(&mut self, x: u16, y: u16, text: &str, _color: Color) -> Result<()> {
+        self.draw(x, y, text)
+    }
+    /// Blanks the line from (`x`, `y`) to its end.
+    fn clear_line_from(&mut self, x: u16, y: u16) -> Result<()>;
+    /// Blanks everything.
+    fn clear(&mut self) -> Result<()>;
+    /// Makes what was drawn visible.
+    fn flush(&mut self) -> Result<()>;
+}
+
+/// Draws straight to a terminal using termion escape codes.
+#[cfg(feature = "terminal")]
+pub struct TerminalSurface<W: Write> {
+    out: W,
+    /// Off to leave everything in the terminal's default color.
+    color: bool,
+}
+
+#[cfg(feature = "terminal")]
+impl<W: Write> TerminalSurface<W> {
+    /// Draws to `out`, with colors unless `color` is off.
+    pub fn new(out: W, color: bool) -> TerminalSurface<W> {
+        TerminalSurface { out, color }
+    }
+}
+
+#[cfg(feature = "terminal")]
+impl<W: Write> Surface for TerminalSurface<W> {
+    fn draw(&mut self, x: u16, y: u16, text: &str) -> Result<()> {
+        write!(self.out, "{}{text}", termion::cursor::Goto(x, y))
+    }
+
+    fn draw_colored(&mut self, x: u16, y: u16, text: &str, color: Color) -> Result<()> {
+        use termion::color::{Fg, Reset};
+        if !self.color {
+            return self.draw(x, y, text);
+        }
+        let goto = termion::cursor::Goto(x, y);
+        match color {
+            Color::Green => write!(self.out, "{goto}{}{text}", Fg(termion::color::Green)),
+            Color::White => write!(self.out, "{goto}{}{text}", Fg(termion::color::White)),
+            Color::Yellow => write!(self.out, "{goto}{}{text}", Fg(termion::color::Yellow)),
+            Color::Red => write!(self.out, "{goto}{}{text}", Fg(termion::color::Red)),
+            Color::Cyan => write!(self.out, "{goto}{}{text}", Fg(termion::color::Cyan)),
+            Color::Magenta => write!(self.out, "{goto}{}{text}", Fg(termion::color::Magenta)),
+        }?;
+        write!(self.out, "{}", Fg(Reset))
+    }
+
+    fn clear_line_from(&mut self, x: u16, y: u16) -> Result<()> {
+        write!(
+            self.out,
+            "{}{}",
+            termion::cursor::Goto(x, y),
+            termion::clear::UntilNewline
+        )
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        write!(
+            self.out,
+            "{}{}{}",
+            termion::clear::All,
+            termion::cursor::Goto(1, 1),
+            termion::cursor::Hide
+        )
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Keeps the screen as an in-memory grid of characters, so it can be inspected without a TTY.
+///
+/// Cells are 1-based like the terminal's. Row and column 0 lie off the grid: they read as
+/// blank, and drawing or clearing there does nothing.
+#[derive(Default)]
+pub struct GridSurface {
+    rows: Vec<Vec<char>>,
+}
+
+impl GridSurface {
+    /// An empty screen.
+    pub fn new() -> GridSurface {
+        GridSurface { rows: Vec::new() }
+    }
+
+    /// Returns the 1-based line `y` without trailing blanks.
+    pub fn line(&self, y: u16) -> String {
+        match self.row(y) {
+            Some(row) => row.iter().collect::<String>().trim_end().to_string(),
+            None => String::new(),
+        }
+    }
+
+    /// The character at the 1-based cell (`x`, `y`), a blank where nothing was drawn.
+    pub fn char_at(&self, x: u16, y: u16) -> char {
+        self.row(y)
+            .zip(index(x))
+            .and_then(|(row, index)| row.get(index))
+            .copied()
+            .unwrap_or(' ')
+    }
+
+    fn row(&self, y: u16) -> Option<&Vec<char>> {
+        self.rows.get(index(y)?)
+    }
+
+    /// Grows the grid down to line `y`, `None` for row 0.
+    fn row_mut(&mut self, y: u16) -> Option<&mut Vec<char>> {
+        let index = index(y)?;
+        if self.rows.len() <= index {
+            self.rows.resize(index + 1, Vec::new());
+        }
+        Some(&mut self.rows[index])
+    }
+}
+
+/// Turns a 1-based cell coordinate into an index, `None` for 0.
+fn index(coordinate: u16) -> Option<usize> {
+    (coordinate as usize).checked_sub(1)
+}
+
+impl Surface for GridSurface {
+    fn draw(&mut self, x: u16, y: u16, text: &str) -> Result<()> {
+        // Check the column first, row_mut grows the grid
+        let Some(start) = index(x) else {
+            return Ok(());
+        };
+        let Some(row) = self.row_mut(y) else {
+            return Ok(());
+        };
+        for (offset, c) in text.chars().enumerate() {
+            let index = start + offset;
+            if row.len() <= index {
+                row.resize(index + 1, ' ');
+            }
+            row[index] = c;
+        }
+        Ok(())
+    }
+
+    fn clear_line_from(&mut self, x: u16, y: u16) -> Result<()> {
+        let row = index(y).and_then(|index| self.rows.get_mut(index));
+        if let (Some(row), Some(start)) = (row, index(x)) {
+            row.truncate(start);
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        self.rows.clear();
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl fmt::Display for GridSurface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = (1..=self.rows.len() as u16).map(|y| self.line(y)).collect();
+        write!(f, "{}", lines.join("\n").trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Balance;
+    use crate::error::AdventureError;
+    use crate::game::{Game, GameAction};
+    use crate::ui::renderer::Renderer;
+    use std::io;
+
+    /// Fails every write, like a terminal that went away.
+    struct BrokenSurface;
+
+    impl Surface for BrokenSurface {
+        fn draw(&mut self, _x: u16, _y: u16, _text: &str) -> Result<()> {
+            Err(io::Error::from(io::ErrorKind::BrokenPipe))
+        }
+
+        fn clear_line_from(&mut self, x: u16, y: u16) -> Result<()> {
+            self.draw(x, y, "")
+        }
+
+        fn clear(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn grid_overwrites_and_clears_cells() {
+        let mut grid = GridSurface::new();
+        grid.draw(3, 2, "abc").unwrap();
+        grid.draw(4, 2, "X").unwrap();
+        assert_eq!(grid.line(1), "");
+        assert_eq!(grid.line(2), "  aXc");
+
+        grid.clear_line_from(4, 2).unwrap();
+        assert_eq!(grid.line(2), "  a");
+        assert_eq!(grid.char_at(3, 2), 'a');
+        assert_eq!(grid.char_at(9, 9), ' ');
+    }
+
+    #[test]
+    fn grid_ignores_row_and_column_zero() {
+        let mut grid = GridSurface::new();
+        grid.draw(0, 1, "a").unwrap();
+        grid.draw(1, 0, "b").unwrap();
+        grid.clear_line_from(0, 0).unwrap();
+        assert_eq!(grid.to_string(), "");
+        assert_eq!(grid.line(0), "");
+        assert_eq!(grid.char_at(0, 0), ' ');
+    }
+
+    #[test]
+    fn game_renders_headless() {
+        let mut game = Game::start(1, Balance::default()).unwrap();
+        let mut renderer = Renderer::new();
+        let mut grid = GridSurface::new();
+        renderer.draw_screen(&game, &mut grid).unwrap();
+        assert!(grid.line(1).starts_with("███      ███ "));
+        assert_eq!(grid.char_at(5, 3), '&');
+
+        let events = game.apply(GameAction::MoveDown);
+        renderer.render(&game, &events, &mut grid).unwrap();
+        assert_eq!(grid.char_at(5, 3), ' ');
+        assert_eq!(grid.char_at(5, 4), '&');
+    }
+
+    #[test]
+    fn draw_errors_name_what_was_drawn() {
+        let mut game = Game::start(1, Balance::default()).unwrap();
+        let events = game.apply(GameAction::MoveDown);
+        let err = Renderer::new()
+            .render(&game, &events, &mut BrokenSurface)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AdventureError::Render {
+                what: "the floor",
+                ..
+            }
+        ));
+    }
+}
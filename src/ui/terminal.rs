@@ -0,0 +1,74 @@
+//! The real terminal in raw mode, restored on exit and on signals.
+
+use signal_hook::consts::{SIGHUP, SIGINT, SIGQUIT, SIGTERM};
+use signal_hook::iterator::Signals;
+use std::io::{self, stdout, Stdout, Write};
+use std::panic;
+use std::process;
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use termion::raw::{IntoRawMode, RawTerminal};
+use termion::screen::{ToAlternateScreen, ToMainScreen};
+
+/// The raw terminal of the running session, taken out again by whoever restores it first.
+static RAW_TERMINAL: Mutex<Option<RawTerminal<Stdout>>> = Mutex::new(None);
+
+/// Puts the terminal in raw mode on the alternate screen for as long as it lives.
+///
+/// The terminal is put back when the session is dropped, when the game panics and when the
+/// process is asked to stop with SIGTERM, SIGINT, SIGQUIT or SIGHUP.
+pub struct TerminalSession {
+    _private: (),
+}
+
+impl TerminalSession {
+    /// Switches the terminal over and installs the handlers that restore it.
+    pub fn start() -> io::Result<TerminalSession> {
+        let mut raw = stdout().into_raw_mode()?;
+        write!(raw, "{ToAlternateScreen}{}", termion::cursor::Hide)?;
+        raw.flush()?;
+        *RAW_TERMINAL.lock().unwrap_or_else(PoisonError::into_inner) = Some(raw);
+        // From here on every way out has to restore the terminal
+        let session = TerminalSession { _private: () };
+
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            // Restore first so the panic message lands on the normal screen
+            restore();
+            previous_hook(info);
+        }));
+
+        let mut signals = Signals::new([SIGTERM, SIGINT, SIGQUIT, SIGHUP])?;
+        thread::spawn(move || {
+            if let Some(signal) = signals.forever().next() {
+                restore();
+                process::exit(128 + signal);
+            }
+        });
+        Ok(session)
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+/// Shows the cursor, leaves the alternate screen and turns raw mode off, once.
+fn restore() {
+    let raw = RAW_TERMINAL
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+    if let Some(mut raw) = raw {
+        // Nothing sensible left to do if this fails, the raw mode is still undone on drop
+        let _ = write!(
+            raw,
+            "{}{}{ToMainScreen}",
+            termion::style::Reset,
+            termion::cursor::Show
+        )
+        .and_then(|_| raw.flush());
+    }
+}